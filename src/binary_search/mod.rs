@@ -1,2 +1,2 @@
-mod upper_bound;
-mod lower_bound;
\ No newline at end of file
+pub mod upper_bound;
+pub mod lower_bound;
\ No newline at end of file
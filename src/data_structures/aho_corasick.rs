@@ -0,0 +1,278 @@
+#![allow(dead_code)]
+use std::collections::VecDeque;
+use crate::data_structures::array_trie::ArrayTrie;
+
+/*
+    Aho-Corasick Automaton
+    --------------------------------------------------------------
+    ArrayTrie of the patterns extended with
+      * failure links: the longest proper suffix of a node that is
+        also a node of the trie
+      * dictionary links: the nearest node on the failure chain
+        where a pattern ends
+      * a complete goto table: the trie arena is completed in place,
+        `next[node][c]` is the state after reading `c`, missing edges
+        are filled from the failure link
+    Build: O(total length * K), Matching: O(text length + matches)
+    --------------------------------------------------------------
+ */
+pub struct AhoCorasick<const K: usize> {
+    // its arena becomes the goto table in `build`
+    trie: ArrayTrie<K>,
+    fail: Vec<u32>,
+    dict_link: Vec<u32>,
+    // ids of the patterns that end exactly at each node
+    ends: Vec<Vec<usize>>,
+    pattern_count: usize,
+    built: bool,
+}
+
+impl AhoCorasick<26> {
+    /// returns: AhoCorasick over `b'a'..=b'z'`
+    pub fn lowercase() -> Self {
+        AhoCorasick::new(|b| (b - b'a') as usize)
+    }
+}
+
+impl<const K: usize> AhoCorasick<K> {
+    /// # Arguments
+    ///
+    /// * `index_of`: Maps every byte of the patterns and texts to `0..K`
+    ///
+    /// returns: AhoCorasick<K>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut automaton = AhoCorasick::<26>::lowercase();
+    /// let he = automaton.insert("he");
+    /// let she = automaton.insert("she");
+    /// automaton.build();
+    /// let matches: Vec<(usize, usize)> = automaton.find_all("ushers").collect(); // [(she, 3), (he, 3)]
+    /// ```
+    pub fn new(index_of: fn(u8) -> usize) -> Self {
+        AhoCorasick {
+            trie: ArrayTrie::new(index_of),
+            fail: Vec::new(),
+            dict_link: Vec::new(),
+            ends: vec![Vec::new()],
+            pattern_count: 0,
+            built: false,
+        }
+    }
+
+    /// Adds a non-empty pattern, it has to be called before `build`
+    ///
+    /// returns: usize, the id of the pattern (patterns are numbered from 0 in insertion order)
+    pub fn insert(&mut self, pattern: &str) -> usize {
+        assert!(!self.built, "AhoCorasick::insert called after build");
+        assert!(!pattern.is_empty(), "AhoCorasick patterns must be non-empty");
+        let end_node = self.trie.insert(pattern);
+        self.ends.resize(self.trie.node_count(), Vec::new());
+        self.ends[end_node].push(self.pattern_count);
+        self.pattern_count += 1;
+        self.pattern_count - 1
+    }
+
+    /// Computes the failure links, the dictionary links and the goto table with a BFS over the trie,
+    /// later calls do nothing since the goto table no longer tells trie edges apart from filled ones
+    pub fn build(&mut self) {
+        if self.built {
+            return;
+        }
+        self.fail = vec![0; self.trie.node_count()];
+        self.dict_link = vec![0; self.trie.node_count()];
+        let next = self.trie.arena_mut();
+        let mut queue = VecDeque::new();
+        // the children of the root fail to the root
+        for &child in next[0].iter() {
+            if child != 0 {
+                queue.push_back(child as usize);
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            let fail = self.fail[node] as usize;
+            self.dict_link[node] = if self.ends[fail].is_empty() { self.dict_link[fail] } else { fail as u32 };
+            // nodes are visited by depth, so the row of `fail` is already complete
+            let fail_row = next[fail];
+            for (child, &fail_child) in next[node].iter_mut().zip(fail_row.iter()) {
+                if *child != 0 {
+                    self.fail[*child as usize] = fail_child;
+                    queue.push_back(*child as usize);
+                } else {
+                    *child = fail_child;
+                }
+            }
+        }
+        self.built = true;
+    }
+
+    /// returns: the number of inserted patterns
+    pub fn pattern_count(&self) -> usize {
+        self.pattern_count
+    }
+
+    /// returns: the state reached from `state` after reading `b`
+    pub fn step(&self, state: usize, b: u8) -> usize {
+        debug_assert!(self.built, "AhoCorasick::build has to be called before matching");
+        self.trie.arena()[state][self.trie.index_of(b)] as usize
+    }
+
+    /// returns: an iterator over every `(pattern_id, end_position)` such that the pattern occurs in `text`
+    /// ending at byte `end_position` (inclusive), ordered by `end_position` and then by decreasing length
+    pub fn find_all<'a>(&'a self, text: &'a str) -> FindAll<'a, K> {
+        debug_assert!(self.built, "AhoCorasick::build has to be called before matching");
+        FindAll {
+            automaton: self,
+            text: text.as_bytes(),
+            position: 0,
+            output_node: 0,
+            output_index: 0,
+            state: 0,
+        }
+    }
+
+    /// returns: for every pattern id, the number of (possibly overlapping) occurrences in `text`
+    pub fn count_occurrences(&self, text: &str) -> Vec<usize> {
+        let mut counts = vec![0; self.pattern_count];
+        for (pattern_id, _) in self.find_all(text) {
+            counts[pattern_id] += 1;
+        }
+        counts
+    }
+}
+
+pub struct FindAll<'a, const K: usize> {
+    automaton: &'a AhoCorasick<K>,
+    text: &'a [u8],
+    // number of bytes of text read so far
+    position: usize,
+    // node on the dictionary chain whose patterns are being reported, 0 when the chain is exhausted
+    output_node: usize,
+    output_index: usize,
+    // automaton state after reading `text[..position]`
+    state: usize,
+}
+
+impl<'a, const K: usize> Iterator for FindAll<'a, K> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        loop {
+            if self.output_node != 0 {
+                let ends = &self.automaton.ends[self.output_node];
+                if self.output_index < ends.len() {
+                    self.output_index += 1;
+                    return Some((ends[self.output_index - 1], self.position - 1));
+                }
+                self.output_node = self.automaton.dict_link[self.output_node] as usize;
+                self.output_index = 0;
+                continue;
+            }
+            if self.position == self.text.len() {
+                return None;
+            }
+            self.state = self.automaton.step(self.state, self.text[self.position]);
+            self.position += 1;
+            self.output_node = if self.automaton.ends[self.state].is_empty() {
+                self.automaton.dict_link[self.state] as usize
+            } else {
+                self.state
+            };
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_aho_corasick() {
+        let mut automaton = AhoCorasick::<26>::lowercase();
+        let he = automaton.insert("he");
+        let she = automaton.insert("she");
+        let his = automaton.insert("his");
+        let hers = automaton.insert("hers");
+        automaton.build();
+        let matches: Vec<(usize, usize)> = automaton.find_all("ushers").collect();
+        assert_eq!(matches, vec![(she, 3), (he, 3), (hers, 5)]);
+        assert_eq!(automaton.count_occurrences("hishershe"), vec![2, 2, 1, 1]);
+        assert_eq!(automaton.find_all("xyz").count(), 0);
+        assert_eq!(his, 2);
+    }
+
+    #[test]
+    fn test_build_twice() {
+        let mut automaton = AhoCorasick::<26>::lowercase();
+        automaton.insert("ab");
+        automaton.insert("b");
+        automaton.build();
+        automaton.build();
+        assert_eq!(automaton.count_occurrences("abab"), vec![2, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "AhoCorasick::insert called after build")]
+    fn test_insert_after_build_panics() {
+        let mut automaton = AhoCorasick::<26>::lowercase();
+        automaton.insert("a");
+        automaton.build();
+        automaton.insert("b");
+    }
+
+    #[test]
+    #[should_panic(expected = "AhoCorasick patterns must be non-empty")]
+    fn test_empty_pattern_panics() {
+        AhoCorasick::<26>::lowercase().insert("");
+    }
+
+    #[test]
+    fn test_duplicate_patterns() {
+        let mut automaton = AhoCorasick::<26>::lowercase();
+        automaton.insert("a");
+        automaton.insert("aa");
+        automaton.insert("a");
+        automaton.build();
+        assert_eq!(automaton.count_occurrences("aaa"), vec![3, 2, 3]);
+    }
+
+    fn count_occurrences_prop(patterns: Vec<String>, text: String) {
+        let mut automaton = AhoCorasick::<26>::lowercase();
+        for pattern in &patterns {
+            automaton.insert(pattern);
+        }
+        automaton.build();
+        let mut expected = Vec::new();
+        for end in 0..text.len() {
+            let mut at_end: Vec<(usize, usize)> = patterns
+                .iter()
+                .enumerate()
+                .filter(|(_, pattern)| text[..=end].ends_with(pattern.as_str()))
+                .map(|(id, _)| (id, end))
+                .collect();
+            expected.append(&mut at_end);
+        }
+        let mut actual: Vec<(usize, usize)> = automaton.find_all(&text).collect();
+        actual.sort_by_key(|&(id, end)| (end, id));
+        assert_eq!(actual, expected);
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn count_occurrences(
+            patterns in vec("[a-c]{1,4}", 1..=20).boxed(),
+            text in "[a-c]{0,100}",
+        ) {
+            count_occurrences_prop(patterns, text);
+        }
+    }
+}
@@ -0,0 +1,203 @@
+#![allow(dead_code)]
+
+/*
+    Array Trie
+    --------------------------------------------------------------
+    Trie over a fixed alphabet of `K` symbols whose nodes are rows of
+    a flat `Vec<[u32; K]>` arena, `next[node][c]` is the child of
+    `node` for symbol `c` and `0` (the root) doubles as "no child".
+    Words are byte strings, `index_of` maps every byte to `0..K`.
+    Insert/Contains: O(len) with no hashing and no per-node allocation
+    --------------------------------------------------------------
+ */
+pub struct ArrayTrie<const K: usize> {
+    next: Vec<[u32; K]>,
+    end_count: Vec<u32>,
+    index_of: fn(u8) -> usize,
+}
+
+impl ArrayTrie<26> {
+    /// returns: ArrayTrie over `b'a'..=b'z'`
+    pub fn lowercase() -> Self {
+        ArrayTrie::new(|b| (b - b'a') as usize)
+    }
+}
+
+impl<const K: usize> ArrayTrie<K> {
+    /// # Arguments
+    ///
+    /// * `index_of`: Maps every byte of the words to `0..K`
+    ///
+    /// returns: ArrayTrie<K>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut trie: ArrayTrie<2> = ArrayTrie::new(|b| (b - b'0') as usize); // binary strings
+    /// trie.insert("0110");
+    /// assert!(trie.contains("0110"));
+    /// ```
+    pub fn new(index_of: fn(u8) -> usize) -> Self {
+        ArrayTrie {
+            next: vec![[0; K]],
+            end_count: vec![0],
+            index_of,
+        }
+    }
+
+    /// Same as `new` with room for `nodes` nodes, at most the total length of the words plus one
+    pub fn with_capacity(index_of: fn(u8) -> usize, nodes: usize) -> Self {
+        let mut a = ArrayTrie::new(index_of);
+        a.next.reserve(nodes);
+        a.end_count.reserve(nodes);
+        a
+    }
+
+    /// returns: usize, the node where `word` ends
+    pub fn insert(&mut self, word: &str) -> usize {
+        let mut current_node = 0;
+
+        for &b in word.as_bytes() {
+            let c = (self.index_of)(b);
+            if self.next[current_node][c] == 0 {
+                self.next.push([0; K]);
+                self.end_count.push(0);
+                self.next[current_node][c] = (self.next.len() - 1) as u32;
+            }
+            current_node = self.next[current_node][c] as usize;
+        }
+        self.end_count[current_node] += 1;
+        current_node
+    }
+
+    fn find(&self, prefix: &str) -> Option<usize> {
+        let mut current_node = 0;
+
+        for &b in prefix.as_bytes() {
+            match self.next[current_node][(self.index_of)(b)] {
+                0 => return None,
+                node => current_node = node as usize,
+            }
+        }
+
+        Some(current_node)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| self.end_count[node] > 0)
+    }
+
+    /// returns: true if `prefix` is a prefix of some inserted word
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.find(prefix).is_some()
+    }
+
+    /// returns: the number of nodes including the root
+    pub fn node_count(&self) -> usize {
+        self.next.len()
+    }
+
+    /// returns: the symbol `index_of` maps `b` to
+    pub fn index_of(&self, b: u8) -> usize {
+        (self.index_of)(b)
+    }
+
+    /// returns: the node arena, `arena()[node][c]` is the child of `node` for symbol `c`, 0 if there is none
+    pub fn arena(&self) -> &[[u32; K]] {
+        &self.next
+    }
+
+    /// Mutable access to the arena for AhoCorasick to fill in its goto table, lookups like `contains` are
+    /// meaningless afterwards so it stays inside data_structures
+    pub(super) fn arena_mut(&mut self) -> &mut [[u32; K]] {
+        &mut self.next
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::trie::Trie;
+    use std::time::Instant;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_array_trie() {
+        let mut trie = ArrayTrie::<26>::lowercase();
+        trie.insert("apple");
+        trie.insert("app");
+        assert!(trie.contains("apple"));
+        assert!(trie.contains("app"));
+        assert!(!trie.contains("appl"));
+        assert!(!trie.contains("b"));
+        assert!(trie.starts_with("appl"));
+        assert_eq!(trie.node_count(), 6);
+        // "ap" ends on an existing node, root -> a -> p
+        assert_eq!(trie.insert("ap"), 2);
+        assert_eq!(trie.node_count(), 6);
+    }
+
+    fn matches_trie_prop(words: Vec<String>, queries: Vec<String>) {
+        let mut trie = Trie::new();
+        let mut array_trie = ArrayTrie::<26>::lowercase();
+        for word in &words {
+            trie.insert(word);
+            array_trie.insert(word);
+        }
+        for query in words.iter().chain(queries.iter()) {
+            assert_eq!(array_trie.contains(query), trie.contains(query));
+            assert_eq!(array_trie.starts_with(query), trie.starts_with(query));
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn matches_trie(
+            words in vec("[a-c]{1,6}", 1..=50).boxed(),
+            queries in vec("[a-d]{0,6}", 1..=50).boxed(),
+        ) {
+            matches_trie_prop(words, queries);
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with cargo test --release -- --ignored"]
+    fn test_array_trie_time() {
+        // 10^6 lowercase characters in words of length 1..=10
+        let mut words = Vec::new();
+        let mut total = 0;
+        while total < 1_000_000 {
+            let len = 1 + rand::random::<usize>() % 10;
+            let word: String = (0..len).map(|_| (b'a' + rand::random::<u8>() % 26) as char).collect();
+            total += len;
+            words.push(word);
+        }
+
+        let start = Instant::now();
+        let mut trie = Trie::new();
+        for word in &words {
+            trie.insert(word);
+        }
+        let hash_map_found = words.iter().filter(|word| trie.contains(word)).count();
+        let hash_map_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut array_trie = ArrayTrie::<26>::with_capacity(|b| (b - b'a') as usize, total + 1);
+        for word in &words {
+            array_trie.insert(word);
+        }
+        let array_found = words.iter().filter(|word| array_trie.contains(word)).count();
+        let array_time = start.elapsed();
+
+        assert_eq!(hash_map_found, words.len());
+        assert_eq!(array_found, words.len());
+        assert!(array_time < hash_map_time, "array trie {:?}, HashMap trie {:?}", array_time, hash_map_time);
+    }
+}
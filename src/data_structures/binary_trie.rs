@@ -0,0 +1,260 @@
+#![allow(dead_code)]
+
+/*
+    Binary Trie
+    --------------------------------------------------------------
+    Multiset of `BITS`-bit keys stored as a trie over their bits,
+    most significant bit first. Every node knows how many keys are
+    in its subtree, so XOR queries walk one root-to-leaf path.
+    Nodes are rows of a flat `Vec<[u32; 2]>` arena, `0` (the root)
+    doubles as "no child"; erased keys leave empty nodes behind.
+    Every operation: O(BITS)
+    --------------------------------------------------------------
+ */
+pub struct BinaryTrie<const BITS: u32> {
+    next: Vec<[u32; 2]>,
+    count: Vec<usize>,
+}
+
+impl<const BITS: u32> Default for BinaryTrie<BITS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const BITS: u32> BinaryTrie<BITS> {
+    /// returns: an empty BinaryTrie, use `BinaryTrie::<32>` for `u32` keys and `BinaryTrie::<64>` for `u64` keys
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut trie = BinaryTrie::<32>::new();
+    /// trie.insert(3);
+    /// trie.insert(10);
+    /// assert_eq!(trie.max_xor(5), Some(15)); // 5 ^ 10
+    /// ```
+    pub fn new() -> Self {
+        assert!((1..=64).contains(&BITS), "BinaryTrie supports 1 to 64 bits, got {}", BITS);
+        BinaryTrie {
+            next: vec![[0; 2]],
+            count: vec![0],
+        }
+    }
+
+    fn __bit(x: u64, i: u32) -> usize {
+        ((x >> i) & 1) as usize
+    }
+
+    /// Adds `delta` copies of `x`, creating the path when needed
+    fn __add(&mut self, x: u64, delta: usize) {
+        debug_assert!(BITS == 64 || x >> BITS == 0, "key {} does not fit in {} bits", x, BITS);
+        let mut current_node = 0;
+        self.count[current_node] += delta;
+        for i in (0..BITS).rev() {
+            let b = Self::__bit(x, i);
+            if self.next[current_node][b] == 0 {
+                self.next.push([0; 2]);
+                self.count.push(0);
+                self.next[current_node][b] = (self.next.len() - 1) as u32;
+            }
+            current_node = self.next[current_node][b] as usize;
+            self.count[current_node] += delta;
+        }
+    }
+
+    /// returns: the non-empty child of `node` on side `b`
+    fn __child(&self, node: usize, b: usize) -> Option<usize> {
+        match self.next[node][b] as usize {
+            0 => None,
+            child if self.count[child] == 0 => None,
+            child => Some(child),
+        }
+    }
+
+    pub fn insert(&mut self, x: u64) {
+        self.__add(x, 1);
+    }
+
+    /// Removes one copy of `x`
+    ///
+    /// returns: false if `x` was not in the trie
+    pub fn erase(&mut self, x: u64) -> bool {
+        if self.count(x) == 0 {
+            return false;
+        }
+        let mut current_node = 0;
+        self.count[current_node] -= 1;
+        for i in (0..BITS).rev() {
+            current_node = self.next[current_node][Self::__bit(x, i)] as usize;
+            self.count[current_node] -= 1;
+        }
+        true
+    }
+
+    /// returns: how many copies of `x` are in the trie
+    pub fn count(&self, x: u64) -> usize {
+        let mut current_node = 0;
+        for i in (0..BITS).rev() {
+            match self.__child(current_node, Self::__bit(x, i)) {
+                Some(node) => current_node = node,
+                None => return 0,
+            }
+        }
+        self.count[current_node]
+    }
+
+    /// returns: the number of keys in the trie (counted with multiplicity)
+    pub fn len(&self) -> usize {
+        self.count[0]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// returns: `min(a ^ x)` over the keys `a` in the trie, None if it is empty
+    pub fn min_xor(&self, x: u64) -> Option<u64> {
+        self.kth_xor(x, 0)
+    }
+
+    /// returns: `max(a ^ x)` over the keys `a` in the trie, None if it is empty
+    pub fn max_xor(&self, x: u64) -> Option<u64> {
+        if self.is_empty() {
+            return None;
+        }
+        self.kth_xor(x, self.len() - 1)
+    }
+
+    /// returns: the number of keys `a` in the trie such that `a ^ x < k`
+    pub fn count_xor_less(&self, x: u64, k: u64) -> usize {
+        // every a ^ x fits in BITS bits
+        if BITS < 64 && k >> BITS != 0 {
+            return self.len();
+        }
+        let mut less = 0;
+        let mut current_node = 0;
+        for i in (0..BITS).rev() {
+            let b = Self::__bit(x, i);
+            // keys that agree with the bit of x give a 0 at bit i of a ^ x
+            if Self::__bit(k, i) == 1 {
+                if let Some(node) = self.__child(current_node, b) {
+                    less += self.count[node];
+                }
+                match self.__child(current_node, b ^ 1) {
+                    Some(node) => current_node = node,
+                    None => return less,
+                }
+            } else {
+                match self.__child(current_node, b) {
+                    Some(node) => current_node = node,
+                    None => return less,
+                }
+            }
+        }
+        less
+    }
+
+    /// returns: the `k`-th (0-indexed) smallest `a ^ x` over the keys `a` in the trie, None if `k >= len()`
+    pub fn kth_xor(&self, x: u64, k: usize) -> Option<u64> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut k = k;
+        let mut result = 0;
+        let mut current_node = 0;
+        for i in (0..BITS).rev() {
+            let b = Self::__bit(x, i);
+            let same = self.__child(current_node, b);
+            let same_count = same.map_or(0, |node| self.count[node]);
+            if k < same_count {
+                current_node = same.unwrap();
+            } else {
+                k -= same_count;
+                result |= 1 << i;
+                current_node = self.__child(current_node, b ^ 1).unwrap();
+            }
+        }
+        Some(result)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_binary_trie() {
+        let mut trie = BinaryTrie::<32>::new();
+        assert_eq!(trie.max_xor(5), None);
+        trie.insert(3);
+        trie.insert(10);
+        trie.insert(10);
+        assert_eq!(trie.count(10), 2);
+        assert_eq!(trie.max_xor(5), Some(15));
+        assert_eq!(trie.min_xor(5), Some(6));
+        assert_eq!(trie.count_xor_less(5, 15), 1);
+        assert_eq!(trie.count_xor_less(5, 16), 3);
+        assert_eq!(trie.kth_xor(5, 1), Some(15));
+        assert!(trie.erase(10));
+        assert!(trie.erase(10));
+        assert!(!trie.erase(10));
+        assert_eq!(trie.max_xor(5), Some(6));
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_u64_keys() {
+        let mut trie = BinaryTrie::<64>::new();
+        trie.insert(u64::MAX);
+        trie.insert(0);
+        assert_eq!(trie.max_xor(1 << 63), Some(1 << 63));
+        assert_eq!(trie.max_xor(1), Some(u64::MAX - 1));
+        assert_eq!(trie.min_xor(u64::MAX - 1), Some(1));
+    }
+
+    fn xor_queries_prop(a: Vec<u64>, erased: Vec<usize>, x: u64, k: u64) {
+        let mut trie = BinaryTrie::<8>::new();
+        for &v in &a {
+            trie.insert(v);
+        }
+        let mut brute = a.clone();
+        for i in erased {
+            let v = a[i % a.len()];
+            let expected = brute.iter().position(|&e| e == v);
+            assert_eq!(trie.erase(v), expected.is_some());
+            if let Some(p) = expected {
+                brute.swap_remove(p);
+            }
+        }
+        let mut xors: Vec<u64> = brute.iter().map(|&e| e ^ x).collect();
+        xors.sort();
+        assert_eq!(trie.len(), brute.len());
+        assert_eq!(trie.min_xor(x), xors.first().copied());
+        assert_eq!(trie.max_xor(x), xors.last().copied());
+        assert_eq!(trie.count_xor_less(x, k), xors.iter().filter(|&&e| e < k).count());
+        for (i, &e) in xors.iter().enumerate() {
+            assert_eq!(trie.kth_xor(x, i), Some(e));
+        }
+        assert_eq!(trie.kth_xor(x, xors.len()), None);
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn xor_queries(
+            a in vec(0u64..256, 1..=100).boxed(),
+            erased in vec(0usize..100, 0..=50).boxed(),
+            x in 0u64..256,
+            k in 0u64..=256,
+        ) {
+            xor_queries_prop(a, erased, x, k);
+        }
+    }
+}
@@ -0,0 +1,444 @@
+#![allow(dead_code)]
+use std::collections::HashMap;
+use crate::data_structures::monoid::{FnMonoid, Monoid};
+
+/*
+    Dynamic (implicit) Segment Tree
+    --------------------------------------------------------------
+    Lazy segment tree over an `i64` coordinate range such as
+    [0, 10^9] or [-10^18, 10^18] whose nodes are only created when
+    an update or a pushed tag reaches them. Every element starts as
+    `default_value`, an absent subtree is answered without creating it.
+    Nodes live in an arena (`Vec`) and refer to their children by
+    `u32` index, `0` (the root) doubles as "no child".
+    Any range of at most u64::MAX coordinates works, lengths are u64.
+    Query: O(log C), Update: O(log C) time, O(log C) new nodes
+    --------------------------------------------------------------
+ */
+struct Node<S, T> {
+    left: u32,
+    right: u32,
+    value: S,
+    lazy: Option<T>,
+}
+
+pub struct DynamicSegmentTree<M, T, CO, AO>
+    where
+        M: Monoid,
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&M::S, &T, usize) -> M::S {
+    range: (i64, i64),
+    nodes: Vec<Node<M::S, T>>,
+    monoid: M,
+    default_value: M::S,
+    // aggregate of `len` default values for every node length, filled once in the constructor, node lengths
+    // on one level differ by at most one so there are at most two per level
+    repeated: HashMap<u64, M::S>,
+    compose_operation: CO,
+    apply_operation: AO,
+}
+
+impl<A: Clone, T: Clone, QO, CO, AO> DynamicSegmentTree<FnMonoid<A, QO>, T, CO, AO>
+    where
+        QO: Fn(&A, &A) -> A,
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&A, &T, usize) -> A {
+    /// # Arguments
+    ///
+    /// * `range`: Inclusive coordinate range `(lo, hi)` covered by the tree, anything but the whole of i64
+    /// * `query_operation`: It is the operation that is performed on the left and right subtree values to get the current node value
+    /// * `compose_operation`: It is the operation that merges a new tag into a pending tag, `compose_operation(old, new)`
+    /// * `apply_operation`: It is the operation that applies a tag to a node value covering `len` elements
+    /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
+    /// * `default_value`: Initial value of every element
+    /// * `capacity`: Number of nodes to allocate up front, about `updates * 4 * log2(hi - lo)`
+    ///
+    /// returns: DynamicSegmentTree<FnMonoid<A, QO>, T, CO, AO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // range add, range sum over [0, 10^9]
+    /// let mut segment_tree = DynamicSegmentTree::new(
+    ///     (0, 1_000_000_000),
+    ///     |a: &i64, b: &i64| a + b,
+    ///     |old: &i64, new: &i64| old + new,
+    ///     |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+    ///     0,
+    ///     0,
+    ///     1 << 20,
+    /// );
+    /// segment_tree.update(&5, (10, 999_999_999)); // add 5 to every value in the range (10, 999_999_999)
+    /// let sum = segment_tree.query((0, 19)); // 50
+    /// ```
+    pub fn new(
+        range: (i64, i64),
+        query_operation: QO,
+        compose_operation: CO,
+        apply_operation: AO,
+        fallback_value: A,
+        default_value: A,
+        capacity: usize,
+    ) -> Self {
+        DynamicSegmentTree::with_monoid(
+            range,
+            FnMonoid::new(query_operation, fallback_value),
+            default_value,
+            compose_operation,
+            apply_operation,
+            capacity,
+        )
+    }
+}
+
+impl<M: Monoid, T: Clone, CO, AO> DynamicSegmentTree<M, T, CO, AO>
+    where
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&M::S, &T, usize) -> M::S {
+    /// # Arguments
+    ///
+    /// * `range`: Inclusive coordinate range `(lo, hi)` covered by the tree, anything but the whole of i64
+    /// * `monoid`: Monoid used to combine the left and right subtree values, its identity is returned for disjoint ranges
+    /// * `default_value`: Initial value of every element
+    /// * `compose_operation`: It is the operation that merges a new tag into a pending tag, `compose_operation(old, new)`
+    /// * `apply_operation`: It is the operation that applies a tag to a node value covering `len` elements
+    /// * `capacity`: Number of nodes to allocate up front, about `updates * 4 * log2(hi - lo)`
+    ///
+    /// returns: DynamicSegmentTree<M, T, CO, AO>
+    pub fn with_monoid(
+        range: (i64, i64),
+        monoid: M,
+        default_value: M::S,
+        compose_operation: CO,
+        apply_operation: AO,
+        capacity: usize,
+    ) -> Self {
+        let mut a = DynamicSegmentTree {
+            range,
+            nodes: Vec::with_capacity(capacity.max(1)),
+            monoid,
+            default_value,
+            repeated: HashMap::new(),
+            compose_operation,
+            apply_operation,
+        };
+        let (lo, hi) = range;
+        assert!(lo <= hi, "DynamicSegmentTree range ({}, {}) is empty", lo, hi);
+        // the length has to fit in u64 and be passed to apply_operation as usize
+        assert!(hi.abs_diff(lo) < u64::MAX, "DynamicSegmentTree cannot cover all of i64, drop one coordinate");
+        let root_value = a.__fill_repeated(hi.abs_diff(lo) + 1);
+        a.nodes.push(Node { left: 0, right: 0, value: root_value, lazy: None });
+        return a;
+    }
+    /// returns: the number of nodes created so far
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Memoizes the aggregate of `len` default values for the node of length `len` and all its descendants,
+    /// their lengths are exactly the halves `len / 2` and `len - len / 2` taken recursively
+    ///
+    /// returns: the aggregate of `len` copies of the default value
+    fn __fill_repeated(&mut self, len: u64) -> M::S {
+        if let Some(value) = self.repeated.get(&len) {
+            return value.clone();
+        }
+        let value = if len == 1 {
+            self.default_value.clone()
+        } else {
+            let half = self.__fill_repeated(len / 2);
+            let rest = self.__fill_repeated(len - len / 2);
+            self.monoid.combine(&half, &rest)
+        };
+        self.repeated.insert(len, value.clone());
+        value
+    }
+    /// returns: the aggregate of the default values of a node of length `len`
+    fn __repeat(&self, len: u64) -> M::S {
+        self.repeated[&len].clone()
+    }
+    /// returns: the midpoint of `cur_range`, the end of its left half
+    fn __mid(cur_range: (i64, i64)) -> i64 {
+        let (cl, cr) = cur_range;
+        cl + (cr.abs_diff(cl) / 2) as i64
+    }
+    fn __new_node(&mut self, len: u64) -> u32 {
+        let value = self.__repeat(len);
+        self.nodes.push(Node { left: 0, right: 0, value, lazy: None });
+        (self.nodes.len() - 1) as u32
+    }
+    /// Applies `tag` to the node covering `len` elements and records it as pending
+    fn __apply(&mut self, node: u32, tag: &T, len: u64) {
+        let cur = &mut self.nodes[node as usize];
+        cur.value = (self.apply_operation)(&cur.value, tag, len as usize);
+        cur.lazy = match &cur.lazy {
+            Some(old) => Some((self.compose_operation)(old, tag)),
+            None => Some(tag.clone()),
+        };
+    }
+    /// Creates the missing children of `node` and moves its pending tag down to them
+    fn __push(&mut self, node: u32, cur_range: (i64, i64)) {
+        let (cl, cr) = cur_range;
+        let cm = Self::__mid(cur_range);
+        let (left_len, right_len) = (cm.abs_diff(cl) + 1, cr.abs_diff(cm));
+        if self.nodes[node as usize].left == 0 {
+            let left = self.__new_node(left_len);
+            self.nodes[node as usize].left = left;
+        }
+        if self.nodes[node as usize].right == 0 {
+            let right = self.__new_node(right_len);
+            self.nodes[node as usize].right = right;
+        }
+        if let Some(tag) = self.nodes[node as usize].lazy.take() {
+            let (left, right) = (self.nodes[node as usize].left, self.nodes[node as usize].right);
+            self.__apply(left, &tag, left_len);
+            self.__apply(right, &tag, right_len);
+        }
+    }
+    /// # Arguments
+    ///
+    /// * `range`: Range of the query
+    ///
+    /// returns: M::S
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query((-1_000_000_000, 1_000_000_000)); // query the sum of the values in the range (-10^9, 10^9) using the query_operation
+    /// ```
+    pub fn query(&mut self, range: (i64, i64)) -> M::S {
+        self.__query(0, range, self.range)
+    }
+    /// # Arguments
+    ///
+    /// * `node`: Index of the current node in the arena
+    /// * `range`: Range of the query
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: `M::S`
+    fn __query(
+        &mut self,
+        node: u32,
+        range: (i64, i64),
+        cur_range: (i64, i64),
+    ) -> M::S {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint, return the identity
+        if cr < l || r < cl {
+            return self.monoid.identity();
+        }
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            return self.nodes[node as usize].value.clone();
+        }
+        let cm = Self::__mid(cur_range);
+        let (left, right) = (self.nodes[node as usize].left, self.nodes[node as usize].right);
+        // an untouched subtree still holds only default values
+        if left == 0 && right == 0 && self.nodes[node as usize].lazy.is_none() {
+            return self.__query_default(range, cur_range);
+        }
+        self.__push(node, cur_range);
+        let (left, right) = (self.nodes[node as usize].left, self.nodes[node as usize].right);
+        let left_subtree_value = self.__query(left, range, (cl, cm));
+        let right_subtree_value = self.__query(right, range, (cm + 1, cr));
+        return self.monoid.combine(&left_subtree_value, &right_subtree_value);
+    }
+    /// Same as `__query` inside an untouched subtree, walks the nodes it would have without creating them
+    fn __query_default(&self, range: (i64, i64), cur_range: (i64, i64)) -> M::S {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        if cr < l || r < cl {
+            return self.monoid.identity();
+        }
+        if l <= cl && cr <= r {
+            return self.__repeat(cr.abs_diff(cl) + 1);
+        }
+        let cm = Self::__mid(cur_range);
+        let left_value = self.__query_default(range, (cl, cm));
+        let right_value = self.__query_default(range, (cm + 1, cr));
+        self.monoid.combine(&left_value, &right_value)
+    }
+    /// # Arguments
+    ///
+    /// * `tag`: Tag to be applied to every value in the range
+    /// * `range`: Range on which the update is to be performed
+    ///
+    /// returns: ()
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// segment_tree.update(&5, (-10, 10)); // apply the tag 5 to every value in the range (-10, 10) using the apply_operation
+    /// ```
+    pub fn update(&mut self, tag: &T, range: (i64, i64)) {
+        self.__update(0, tag, range, self.range);
+    }
+    /// # Arguments
+    ///
+    /// * `node`: Index of the current node in the arena
+    /// * `tag`: Tag to be applied
+    /// * `range`: Range on which the update is to be performed
+    /// * `cur_range`: Current range of the node in the segment tree
+    ///
+    /// returns: ()
+    fn __update(
+        &mut self,
+        node: u32,
+        tag: &T,
+        range: (i64, i64),
+        cur_range: (i64, i64),
+    ) {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint
+        if cr < l || r < cl {
+            return;
+        }
+        // if cur_range is inside range, tag the node and stop here
+        if l <= cl && cr <= r {
+            self.__apply(node, tag, cr.abs_diff(cl) + 1);
+            return;
+        }
+        self.__push(node, cur_range);
+        let cm = Self::__mid(cur_range);
+        let (left, right) = (self.nodes[node as usize].left, self.nodes[node as usize].right);
+        self.__update(left, tag, range, (cl, cm));
+        self.__update(right, tag, range, (cm + 1, cr));
+        self.nodes[node as usize].value = self.monoid.combine(
+            &self.nodes[left as usize].value,
+            &self.nodes[right as usize].value,
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::lazy_segment_tree::LazySegmentTree;
+    use crate::data_structures::monoid::Min;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_huge_range_add_sum() {
+        let mut segment_tree = DynamicSegmentTree::new(
+            (0, 1_000_000_000),
+            |a: &i64, b: &i64| a + b,
+            |old: &i64, new: &i64| old + new,
+            |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+            0,
+            0,
+            1 << 10,
+        );
+        segment_tree.update(&5, (10, 999_999_999));
+        assert_eq!(segment_tree.query((0, 19)), 50);
+        assert_eq!(segment_tree.query((0, 1_000_000_000)), 5 * 999_999_990);
+        segment_tree.update(&-5, (500, 500));
+        assert_eq!(segment_tree.query((0, 1_000)), 5 * 990);
+        assert!(segment_tree.node_count() < 200);
+    }
+
+    #[test]
+    fn test_default_value() {
+        // range assign, range min over [-10^18, 10^18] where every element starts at 7
+        let mut segment_tree = DynamicSegmentTree::with_monoid(
+            (-1_000_000_000_000_000_000, 1_000_000_000_000_000_000),
+            Min::<i64>::new(),
+            7,
+            |_old: &i64, new: &i64| *new,
+            |_a: &i64, tag: &i64, _len: usize| *tag,
+            0,
+        );
+        assert_eq!(segment_tree.query((-5, 5)), 7);
+        segment_tree.update(&3, (-1_000_000_000_000, 0));
+        assert_eq!(segment_tree.query((1, 1_000_000_000_000_000_000)), 7);
+        assert_eq!(segment_tree.query((0, 1)), 3);
+        segment_tree.update(&9, (-2, 2));
+        assert_eq!(segment_tree.query((-2, 2)), 9);
+        assert_eq!(segment_tree.query((-3, 2)), 3);
+    }
+
+    #[test]
+    fn test_extreme_coordinates() {
+        // every coordinate but i64::MAX, the root covers u64::MAX elements
+        let mut segment_tree = DynamicSegmentTree::with_monoid(
+            (i64::MIN, i64::MAX - 1),
+            Min::<i64>::new(),
+            7,
+            |_old: &i64, new: &i64| *new,
+            |_a: &i64, tag: &i64, _len: usize| *tag,
+            0,
+        );
+        assert_eq!(segment_tree.query((i64::MIN, i64::MAX - 1)), 7);
+        segment_tree.update(&-1, (i64::MIN, i64::MIN));
+        segment_tree.update(&2, (i64::MAX - 1, i64::MAX - 1));
+        assert_eq!(segment_tree.query((i64::MIN, i64::MAX - 1)), -1);
+        assert_eq!(segment_tree.query((i64::MIN + 1, i64::MAX - 1)), 2);
+        // the default aggregates are only kept for node lengths, partial queries do not add entries
+        let memo_len = segment_tree.repeated.len();
+        assert!(memo_len <= 2 * 64);
+        for k in 1..1000 {
+            segment_tree.query((-k * 1_000_003, k * 999_983));
+        }
+        assert_eq!(segment_tree.repeated.len(), memo_len);
+    }
+
+    #[test]
+    #[should_panic(expected = "DynamicSegmentTree cannot cover all of i64")]
+    fn test_whole_i64_range_panics() {
+        DynamicSegmentTree::with_monoid(
+            (i64::MIN, i64::MAX),
+            Min::<i64>::new(),
+            7,
+            |_old: &i64, new: &i64| *new,
+            |_a: &i64, tag: &i64, _len: usize| *tag,
+            0,
+        );
+    }
+
+    fn matches_lazy_segment_tree_prop(offset: i64, ops: Vec<(usize, usize, i64)>) {
+        let n = 100;
+        let mut lazy = LazySegmentTree::new(
+            &vec![1i64; n],
+            |a: &i64, b: &i64| a + b,
+            |old: &i64, new: &i64| old + new,
+            |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+            0,
+        );
+        let mut dynamic = DynamicSegmentTree::new(
+            (offset, offset + n as i64 - 1),
+            |a: &i64, b: &i64| a + b,
+            |old: &i64, new: &i64| old + new,
+            |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+            0,
+            1,
+            0,
+        );
+        for (x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            let shifted = (offset + l as i64, offset + r as i64);
+            if v % 2 == 0 {
+                lazy.update(&v, l..=r);
+                dynamic.update(&v, shifted);
+            } else {
+                assert_eq!(dynamic.query(shifted), lazy.query(l..=r));
+            }
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn matches_lazy_segment_tree(
+            offset in -1_000_000_000_000i64..=1_000_000_000_000,
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            matches_lazy_segment_tree_prop(offset, ops);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+#![allow(dead_code)]
+use std::ops::{Add, Mul, Sub};
+
+/*
+    Fenwick Tree (Binary Indexed Tree)
+    --------------------------------------------------------------
+    `tree_arr[i]` (1-indexed) holds the sum of the `i & -i` elements
+    ending at position `i`, so every prefix is a sum of O(log n) nodes.
+    The public API is 0-indexed and ranges are inclusive like SegmentTree.
+    Build: O(n), Update: O(log n), Query: O(log n)
+    --------------------------------------------------------------
+ */
+pub struct FenwickTree<T> {
+    n: usize,
+    tree_arr: Vec<T>,
+}
+
+impl<T> FenwickTree<T>
+    where
+        T: Copy + Default + Add<Output=T> + Sub<Output=T> {
+    /// returns: FenwickTree<T> over `n` zeroes (`T::default()`)
+    pub fn new(n: usize) -> Self {
+        FenwickTree {
+            n,
+            tree_arr: vec![T::default(); n + 1],
+        }
+    }
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the fenwick tree is built
+    ///
+    /// returns: FenwickTree<T>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut fenwick_tree = FenwickTree::from_vec(&vec![1, 2, 3, 4]);
+    /// fenwick_tree.add(1, 10); // a[1] += 10
+    /// let sum = fenwick_tree.range_sum((1, 2)); // 15
+    /// ```
+    pub fn from_vec(inp_arr: &Vec<T>) -> Self {
+        let n = inp_arr.len();
+        let mut tree_arr = vec![T::default(); n + 1];
+        tree_arr[1..].copy_from_slice(inp_arr);
+        // push every node into its parent once, O(n)
+        for i in 1..=n {
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= n {
+                tree_arr[parent] = tree_arr[parent] + tree_arr[i];
+            }
+        }
+        FenwickTree { n, tree_arr }
+    }
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+    /// Adds `delta` to `a[index]`
+    pub fn add(&mut self, index: usize, delta: T) {
+        let mut i = index + 1;
+        while i <= self.n {
+            self.tree_arr[i] = self.tree_arr[i] + delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+    /// returns: the sum of the first `len` elements, `a[0] + ... + a[len - 1]`
+    pub fn prefix_sum(&self, len: usize) -> T {
+        let mut sum = T::default();
+        let mut i = len;
+        while i > 0 {
+            sum = sum + self.tree_arr[i];
+            i &= i - 1;
+        }
+        sum
+    }
+    /// returns: the sum of `a[l..=r]`
+    pub fn range_sum(&self, range: (usize, usize)) -> T {
+        let (l, r) = range;
+        self.prefix_sum(r + 1) - self.prefix_sum(l)
+    }
+}
+
+impl<T> FenwickTree<T>
+    where
+        T: Copy + Default + Add<Output=T> + Sub<Output=T> + Ord {
+    ///
+    /// Returns the first index `i` such that `a[0] + ... + a[i] >= value`, all elements must be non-negative
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: the prefix sum to search for
+    ///
+    /// returns: usize, `n` if the total sum is less than `value`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let fenwick_tree = FenwickTree::from_vec(&vec![1, 0, 2, 3]);
+    /// assert_eq!(fenwick_tree.lower_bound(3), 2);
+    /// ```
+    pub fn lower_bound(&self, value: T) -> usize {
+        if value <= T::default() {
+            return 0;
+        }
+        let mut pos = 0;
+        let mut remaining = value;
+        let mut step = if self.n == 0 { 0 } else { 1 << (usize::BITS - 1 - self.n.leading_zeros()) };
+        while step > 0 {
+            if pos + step <= self.n && self.tree_arr[pos + step] < remaining {
+                pos += step;
+                remaining = remaining - self.tree_arr[pos];
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+/*
+    Range update, point query: a fenwick tree over the difference array
+ */
+pub struct RangeUpdateFenwickTree<T> {
+    diff: FenwickTree<T>,
+}
+
+impl<T> RangeUpdateFenwickTree<T>
+    where
+        T: Copy + Default + Add<Output=T> + Sub<Output=T> {
+    pub fn new(n: usize) -> Self {
+        RangeUpdateFenwickTree {
+            diff: FenwickTree::new(n + 1),
+        }
+    }
+    pub fn from_vec(inp_arr: &Vec<T>) -> Self {
+        let n = inp_arr.len();
+        let mut diff = vec![T::default(); n + 1];
+        for i in 0..n {
+            diff[i] = diff[i] + inp_arr[i];
+            diff[i + 1] = diff[i + 1] - inp_arr[i];
+        }
+        RangeUpdateFenwickTree {
+            diff: FenwickTree::from_vec(&diff),
+        }
+    }
+    /// Adds `delta` to every element of `a[l..=r]`
+    pub fn range_add(&mut self, range: (usize, usize), delta: T) {
+        let (l, r) = range;
+        self.diff.add(l, delta);
+        self.diff.add(r + 1, T::default() - delta);
+    }
+    /// returns: `a[index]`
+    pub fn get(&self, index: usize) -> T {
+        self.diff.prefix_sum(index + 1)
+    }
+}
+
+/*
+    Range update, range query: two fenwick trees over the difference array d,
+    a[0] + ... + a[i - 1] = i * (d[0] + ... + d[i - 1]) - (0 * d[0] + ... + (i - 1) * d[i - 1])
+ */
+pub struct RangeFenwickTree<T> {
+    diff: FenwickTree<T>,
+    weighted_diff: FenwickTree<T>,
+}
+
+impl<T> RangeFenwickTree<T>
+    where
+        T: Copy + Default + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + TryFrom<usize> {
+    pub fn new(n: usize) -> Self {
+        RangeFenwickTree {
+            diff: FenwickTree::new(n + 1),
+            weighted_diff: FenwickTree::new(n + 1),
+        }
+    }
+    pub fn from_vec(inp_arr: &Vec<T>) -> Self {
+        let mut a = RangeFenwickTree::new(inp_arr.len());
+        for (i, &value) in inp_arr.iter().enumerate() {
+            a.range_add((i, i), value);
+        }
+        a
+    }
+    /// returns: `index` as a T, panics instead of wrapping when it does not fit
+    fn __weight(index: usize) -> T {
+        T::try_from(index).unwrap_or_else(|_| panic!("index {} does not fit in the element type", index))
+    }
+    fn __add(&mut self, index: usize, delta: T) {
+        self.diff.add(index, delta);
+        self.weighted_diff.add(index, Self::__weight(index) * delta);
+    }
+    /// Adds `delta` to every element of `a[l..=r]`
+    pub fn range_add(&mut self, range: (usize, usize), delta: T) {
+        let (l, r) = range;
+        self.__add(l, delta);
+        self.__add(r + 1, T::default() - delta);
+    }
+    /// returns: the sum of the first `len` elements, `a[0] + ... + a[len - 1]`
+    pub fn prefix_sum(&self, len: usize) -> T {
+        Self::__weight(len) * self.diff.prefix_sum(len) - self.weighted_diff.prefix_sum(len)
+    }
+    /// returns: the sum of `a[l..=r]`
+    pub fn range_sum(&self, range: (usize, usize)) -> T {
+        let (l, r) = range;
+        self.prefix_sum(r + 1) - self.prefix_sum(l)
+    }
+}
+
+/*
+    2D Fenwick Tree: point update, rectangle sum in O(log n * log m)
+ */
+pub struct FenwickTree2D<T> {
+    n: usize,
+    m: usize,
+    tree_arr: Vec<Vec<T>>,
+}
+
+impl<T> FenwickTree2D<T>
+    where
+        T: Copy + Default + Add<Output=T> + Sub<Output=T> {
+    pub fn new(n: usize, m: usize) -> Self {
+        FenwickTree2D {
+            n,
+            m,
+            tree_arr: vec![vec![T::default(); m + 1]; n + 1],
+        }
+    }
+    /// Adds `delta` to `a[x][y]`
+    pub fn add(&mut self, x: usize, y: usize, delta: T) {
+        let mut i = x + 1;
+        while i <= self.n {
+            let mut j = y + 1;
+            while j <= self.m {
+                self.tree_arr[i][j] = self.tree_arr[i][j] + delta;
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+    /// returns: the sum of `a[i][j]` for `i < rows` and `j < cols`
+    pub fn prefix_sum(&self, rows: usize, cols: usize) -> T {
+        let mut sum = T::default();
+        let mut i = rows;
+        while i > 0 {
+            let mut j = cols;
+            while j > 0 {
+                sum = sum + self.tree_arr[i][j];
+                j &= j - 1;
+            }
+            i &= i - 1;
+        }
+        sum
+    }
+    /// returns: the sum of `a[i][j]` for `x1 <= i <= x2` and `y1 <= j <= y2`
+    pub fn rect_sum(&self, top_left: (usize, usize), bottom_right: (usize, usize)) -> T {
+        let (x1, y1) = top_left;
+        let (x2, y2) = bottom_right;
+        self.prefix_sum(x2 + 1, y2 + 1) - self.prefix_sum(x1, y2 + 1) - self.prefix_sum(x2 + 1, y1)
+            + self.prefix_sum(x1, y1)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_fenwick_tree() {
+        let mut fenwick_tree = FenwickTree::from_vec(&vec![1i64, 2, 3, 4]);
+        assert_eq!(fenwick_tree.prefix_sum(0), 0);
+        assert_eq!(fenwick_tree.prefix_sum(4), 10);
+        fenwick_tree.add(1, 10);
+        assert_eq!(fenwick_tree.range_sum((1, 2)), 15);
+        assert_eq!(fenwick_tree.range_sum((3, 3)), 4);
+    }
+
+    #[test]
+    fn test_range_fenwick_tree() {
+        let mut fenwick_tree = RangeFenwickTree::from_vec(&vec![1i32, 2, 3, 4]);
+        fenwick_tree.range_add((1, 3), 5);
+        assert_eq!(fenwick_tree.prefix_sum(4), 25);
+        assert_eq!(fenwick_tree.range_sum((2, 3)), 17);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 128 does not fit in the element type")]
+    fn test_range_fenwick_tree_weight_overflow_panics() {
+        // i8 can hold the values but not the weight of index 128
+        let fenwick_tree = RangeFenwickTree::<i8>::new(200);
+        fenwick_tree.prefix_sum(128);
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        let fenwick_tree = FenwickTree::from_vec(&vec![1i64, 0, 2, 3]);
+        assert_eq!(fenwick_tree.lower_bound(0), 0);
+        assert_eq!(fenwick_tree.lower_bound(1), 0);
+        assert_eq!(fenwick_tree.lower_bound(2), 2);
+        assert_eq!(fenwick_tree.lower_bound(3), 2);
+        assert_eq!(fenwick_tree.lower_bound(6), 3);
+        assert_eq!(fenwick_tree.lower_bound(7), 4);
+    }
+
+    fn point_update_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut brute = a.clone();
+        let mut fenwick_tree = FenwickTree::from_vec(&a);
+        for (x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            fenwick_tree.add(x % n, v);
+            brute[x % n] += v;
+            assert_eq!(fenwick_tree.range_sum((l, r)), brute[l..=r].iter().sum::<i64>());
+        }
+    }
+
+    fn lower_bound_prop(a: Vec<i64>, x: i64) {
+        let fenwick_tree = FenwickTree::from_vec(&a);
+        let first_prefix_greater_or_equal: usize = (|| {
+            let mut sum = 0;
+            for i in 0..a.len() {
+                sum += a[i];
+                if sum >= x {
+                    return i;
+                }
+            }
+            a.len()
+        })();
+        assert_eq!(fenwick_tree.lower_bound(x), first_prefix_greater_or_equal);
+    }
+
+    fn range_update_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut brute = a.clone();
+        let mut point_query = RangeUpdateFenwickTree::from_vec(&a);
+        let mut range_query = RangeFenwickTree::from_vec(&a);
+        for (x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            point_query.range_add((l, r), v);
+            range_query.range_add((l, r), v);
+            for item in brute.iter_mut().take(r + 1).skip(l) {
+                *item += v;
+            }
+            assert_eq!(point_query.get(x % n), brute[x % n]);
+            assert_eq!(range_query.range_sum((l, r)), brute[l..=r].iter().sum::<i64>());
+            assert_eq!(range_query.prefix_sum(y % n), brute[..y % n].iter().sum::<i64>());
+        }
+    }
+
+    fn fenwick_tree_2d_prop(ops: Vec<(usize, usize, usize, usize, i64)>) {
+        let (n, m) = (7, 11);
+        let mut brute = vec![vec![0i64; m]; n];
+        let mut fenwick_tree = FenwickTree2D::new(n, m);
+        for (x1, y1, x2, y2, v) in ops {
+            fenwick_tree.add(x1 % n, y1 % m, v);
+            brute[x1 % n][y1 % m] += v;
+            let (xl, xr) = ((x1 % n).min(x2 % n), (x1 % n).max(x2 % n));
+            let (yl, yr) = ((y1 % m).min(y2 % m), (y1 % m).max(y2 % m));
+            let expected: i64 = brute[xl..=xr].iter().map(|row| row[yl..=yr].iter().sum::<i64>()).sum();
+            assert_eq!(fenwick_tree.rect_sum((xl, yl), (xr, yr)), expected);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn point_update(
+            a in vec(-1000i64..=1000, 1..=100).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            point_update_prop(a, ops);
+        }
+        #[test]
+        fn lower_bound(a in vec(0i64..=100, 0..=100).boxed(), x in 0i64..=10_000) {
+            lower_bound_prop(a, x);
+        }
+        #[test]
+        fn range_update(
+            a in vec(-1000i64..=1000, 1..=100).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            range_update_prop(a, ops);
+        }
+        #[test]
+        fn fenwick_tree_2d(ops in vec((0usize..100, 0usize..100, 0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed()) {
+            fenwick_tree_2d_prop(ops);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+#![allow(dead_code)]
+use crate::data_structures::monoid::{FnMonoid, Monoid};
+
+/*
+    Iterative Segment Tree
+    --------------------------------------------------------------
+    Bottom-up segment tree over `2 * n` nodes, the leaves live in
+    `tree_arr[n..2n]` and node `i` is the parent of `2i` and `2i + 1`.
+    Works for any `n` (no padding to a power of two) and keeps the
+    order of the operands, so non-commutative monoids are fine.
+    Build: O(n), Query: O(log n), Update: O(k + log n) for k leaves
+    --------------------------------------------------------------
+ */
+pub struct IterativeSegmentTree<M, NO = fn(&<M as Monoid>::S, &<M as Monoid>::S) -> <M as Monoid>::S>
+    where
+        M: Monoid,
+        NO: Fn(&M::S, &M::S) -> M::S {
+    n: usize,
+    tree_arr: Vec<M::S>,
+    monoid: M,
+    node_operation: NO,
+}
+
+impl<A: Clone, QO, NO> IterativeSegmentTree<FnMonoid<A, QO>, NO>
+    where
+        QO: Fn(&A, &A) -> A,
+        NO: Fn(&A, &A) -> A {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `query_operation`: It is the operation that is performed on the left and right subtree values to get the current node value
+    /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
+    /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
+    ///
+    /// returns: IterativeSegmentTree<FnMonoid<A, QO>, NO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let inp_arr: Vec<i64> = vec![0; 4];
+    /// let mut segment_tree = IterativeSegmentTree::new(
+    ///    &inp_arr,
+    ///   |a: &i64, b: &i64| *a.max(b),
+    ///   |a: &i64, b: &i64| a + b,
+    ///   i64::MIN,
+    /// );
+    /// segment_tree.update(&1, (0, 3)); // add 1 to every value in the range (0, 3) using the node_operation
+    /// let max = segment_tree.query((0, inp_arr.len() - 1)); // query the maximum value in the range (0, inp_arr.len() - 1) using the query_operation
+    /// ```
+    pub fn new(
+        inp_arr: &Vec<A>,
+        query_operation: QO,
+        node_operation: NO,
+        fallback_value: A,
+    ) -> Self {
+        IterativeSegmentTree::with_monoid(inp_arr, FnMonoid::new(query_operation, fallback_value), node_operation)
+    }
+}
+
+impl<M: Monoid> IterativeSegmentTree<M> {
+    /// Builds a segment tree whose `update` assigns the new value to the leaves
+    ///
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values
+    ///
+    /// returns: IterativeSegmentTree<M>
+    pub fn from_monoid(inp_arr: &Vec<M::S>, monoid: M) -> Self {
+        IterativeSegmentTree::with_monoid(inp_arr, monoid, |_: &M::S, b: &M::S| b.clone())
+    }
+}
+
+impl<M: Monoid, NO> IterativeSegmentTree<M, NO>
+    where
+        NO: Fn(&M::S, &M::S) -> M::S {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values, its identity is returned for empty ranges
+    /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
+    ///
+    /// returns: IterativeSegmentTree<M, NO>
+    pub fn with_monoid(
+        inp_arr: &Vec<M::S>,
+        monoid: M,
+        node_operation: NO,
+    ) -> Self {
+        let n = inp_arr.len();
+        let mut tree_arr: Vec<M::S> = Vec::with_capacity(2 * n);
+        tree_arr.resize(n, monoid.identity());
+        tree_arr.extend(inp_arr.iter().cloned());
+        for i in (1..n).rev() {
+            tree_arr[i] = monoid.combine(&tree_arr[2 * i], &tree_arr[2 * i + 1]);
+        }
+        IterativeSegmentTree {
+            n,
+            tree_arr,
+            monoid,
+            node_operation,
+        }
+    }
+    /// # Arguments
+    ///
+    /// * `range`: Range of the query (inclusive)
+    ///
+    /// returns: M::S
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query((0, inp_arr.len() - 1)); // query the maximum value in the range (0, inp_arr.len() - 1) using the query_operation
+    /// ```
+    pub fn query(&self, range: (usize, usize)) -> M::S {
+        let (l, r) = range;
+        let mut left_value = self.monoid.identity();
+        let mut right_value = self.monoid.identity();
+        let mut l = l + self.n;
+        let mut r = r + self.n + 1;
+        while l < r {
+            if l & 1 == 1 {
+                left_value = self.monoid.combine(&left_value, &self.tree_arr[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                right_value = self.monoid.combine(&self.tree_arr[r], &right_value);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        self.monoid.combine(&left_value, &right_value)
+    }
+    /// # Arguments
+    ///
+    /// * `value`: New Value to be updated
+    /// * `range`: Range on which the update is to be performed (inclusive)
+    ///
+    /// returns: ()
+    pub fn update(
+        &mut self,
+        value: &M::S,
+        range: (usize, usize),
+    ) {
+        let (l, r) = range;
+        let mut l = l + self.n;
+        let mut r = r + self.n;
+        for i in l..=r {
+            self.tree_arr[i] = (self.node_operation)(&self.tree_arr[i], value);
+        }
+        // every level above the leaves has a contiguous run of dirty parents
+        while l > 1 {
+            l >>= 1;
+            r >>= 1;
+            for i in l..=r {
+                self.tree_arr[i] = self.monoid.combine(&self.tree_arr[2 * i], &self.tree_arr[2 * i + 1]);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::monoid::{Affine, Sum};
+    use crate::data_structures::segment_tree::SegmentTree;
+    use std::time::Instant;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_iterative_segment_tree() {
+        let inp_arr: Vec<i64> = vec![5, 3, 8, 6, 1];
+        let mut segment_tree = IterativeSegmentTree::new(
+            &inp_arr,
+            |a: &i64, b: &i64| *a.max(b),
+            |a: &i64, b: &i64| a + b,
+            i64::MIN,
+        );
+        assert_eq!(segment_tree.query((0, 4)), 8);
+        assert_eq!(segment_tree.query((3, 4)), 6);
+        segment_tree.update(&10, (3, 4));
+        assert_eq!(segment_tree.query((0, 2)), 8);
+        assert_eq!(segment_tree.query((4, 4)), 11);
+        assert_eq!(segment_tree.query((0, 4)), 16);
+    }
+
+    #[test]
+    fn test_non_commutative() {
+        // compose the maps x -> 2x + i in order over an odd number of leaves
+        let inp_arr: Vec<(i64, i64)> = (0..7).map(|i| (2, i)).collect();
+        let segment_tree = IterativeSegmentTree::from_monoid(&inp_arr, Affine::<i64>::new());
+        for l in 0..7 {
+            for r in l..7 {
+                let expected = inp_arr[l..=r].iter().fold(1, |x, f| Affine::apply(f, &x));
+                assert_eq!(Affine::apply(&segment_tree.query((l, r)), &1), expected);
+            }
+        }
+    }
+
+    fn iterative_matches_recursive_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut iterative = IterativeSegmentTree::new(&a, |a: &i64, b: &i64| a + b, |a: &i64, b: &i64| a + b, 0);
+        let mut recursive = SegmentTree::new(&a, |a: &i64, b: &i64| a + b, |a: &i64, b: &i64| a + b, 0);
+        for (x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            if v % 2 == 0 {
+                iterative.update(&v, (l, r));
+                recursive.update(&v, l..=r);
+            } else {
+                assert_eq!(iterative.query((l, r)), recursive.query(l..=r));
+            }
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn iterative_matches_recursive(
+            a in vec(-1000i64..=1000, 1..=100).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            iterative_matches_recursive_prop(a, ops);
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with cargo test --release -- --ignored"]
+    fn test_iterative_segment_tree_time() {
+        let n = 200_000;
+        let inp_arr: Vec<i64> = (0..n).map(|_| rand::random::<i64>() % 1_000_000_000).collect();
+        let ops: Vec<(usize, usize, i64)> = (0..n)
+            .map(|_| {
+                let (x, y) = (rand::random::<usize>() % n, rand::random::<usize>() % n);
+                (x.min(y), x.max(y), rand::random::<i64>() % 1_000_000_000)
+            })
+            .collect();
+
+        let start = Instant::now();
+        let mut recursive: SegmentTree<Sum<i64>> = SegmentTree::from_monoid(&inp_arr, Sum::new());
+        let mut recursive_checksum = 0i64;
+        for &(l, r, v) in &ops {
+            recursive.update(&v, l..=l);
+            recursive_checksum = recursive_checksum.wrapping_add(recursive.query(l..=r));
+        }
+        let recursive_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut iterative: IterativeSegmentTree<Sum<i64>> = IterativeSegmentTree::from_monoid(&inp_arr, Sum::new());
+        let mut iterative_checksum = 0i64;
+        for &(l, r, v) in &ops {
+            iterative.update(&v, (l, l));
+            iterative_checksum = iterative_checksum.wrapping_add(iterative.query((l, r)));
+        }
+        let iterative_time = start.elapsed();
+
+        assert_eq!(recursive_checksum, iterative_checksum);
+        assert!(iterative_time < recursive_time, "iterative {:?}, recursive {:?}", iterative_time, recursive_time);
+    }
+}
@@ -0,0 +1,390 @@
+#![allow(dead_code)]
+use std::ops::RangeBounds;
+use crate::data_structures::monoid::{FnMonoid, Monoid};
+use crate::data_structures::segment_tree::{__bounds, __checked_bounds, SegmentTreeError};
+
+pub struct LazySegmentTree<M, T, CO, AO>
+    where
+        M: Monoid,
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&M::S, &T, usize) -> M::S {
+    n: usize,
+    tree_arr: Vec<M::S>,
+    lazy_arr: Vec<Option<T>>,
+    monoid: M,
+    compose_operation: CO,
+    apply_operation: AO,
+}
+
+impl<A: Clone, T: Clone, QO, CO, AO> LazySegmentTree<FnMonoid<A, QO>, T, CO, AO>
+    where
+        QO: Fn(&A, &A) -> A,
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&A, &T, usize) -> A {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `query_operation`: It is the operation that is performed on the left and right subtree values to get the current node value
+    /// * `compose_operation`: It is the operation that merges a new tag into a pending tag, `compose_operation(old, new)`
+    /// * `apply_operation`: It is the operation that applies a tag to a node value covering `len` elements
+    /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
+    ///
+    /// returns: LazySegmentTree<FnMonoid<A, QO>, T, CO, AO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // range add, range sum
+    /// let inp_arr: Vec<i64> = vec![0; 4];
+    /// let mut segment_tree = LazySegmentTree::new(
+    ///     &inp_arr,
+    ///     |a: &i64, b: &i64| a + b,
+    ///     |old: &i64, new: &i64| old + new,
+    ///     |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+    ///     0,
+    /// );
+    /// segment_tree.update(&5, 1..=3); // add 5 to every value in the range (1, 3)
+    /// let sum = segment_tree.query(..); // 15
+    /// ```
+    pub fn new(
+        inp_arr: &Vec<A>,
+        query_operation: QO,
+        compose_operation: CO,
+        apply_operation: AO,
+        fallback_value: A,
+    ) -> Self {
+        LazySegmentTree::with_monoid(
+            inp_arr,
+            FnMonoid::new(query_operation, fallback_value),
+            compose_operation,
+            apply_operation,
+        )
+    }
+}
+
+impl<M: Monoid, T: Clone, CO, AO> LazySegmentTree<M, T, CO, AO>
+    where
+        CO: Fn(&T, &T) -> T,
+        AO: Fn(&M::S, &T, usize) -> M::S {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values, its identity is returned for disjoint ranges
+    /// * `compose_operation`: It is the operation that merges a new tag into a pending tag, `compose_operation(old, new)`
+    /// * `apply_operation`: It is the operation that applies a tag to a node value covering `len` elements
+    ///
+    /// returns: LazySegmentTree<M, T, CO, AO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // range assign, range min
+    /// let mut segment_tree = LazySegmentTree::with_monoid(
+    ///     &vec![5i64, 3, 8, 6],
+    ///     Min::<i64>::new(),
+    ///     |_old: &i64, new: &i64| *new,
+    ///     |_a: &i64, tag: &i64, _len: usize| *tag,
+    /// );
+    /// ```
+    pub fn with_monoid(
+        inp_arr: &Vec<M::S>,
+        monoid: M,
+        compose_operation: CO,
+        apply_operation: AO,
+    ) -> Self {
+        let n = inp_arr.len();
+        let tree_arr: Vec<M::S> = vec![monoid.identity(); 4 * n];
+        let lazy_arr: Vec<Option<T>> = vec![None; 4 * n];
+        let mut a = LazySegmentTree {
+            n,
+            tree_arr,
+            lazy_arr,
+            monoid,
+            compose_operation,
+            apply_operation,
+        };
+        // an empty tree has no nodes to build, every query on it is the empty range
+        if n > 0 {
+            a.__build(inp_arr, 0, (0, n - 1));
+        }
+        return a;
+    }
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Input array on which the segment tree is built
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: ()
+    fn __build(
+        &mut self,
+        inp_arr: &Vec<M::S>,
+        tree_index: usize,
+        cur_range: (usize, usize),
+    ) {
+        let (cl, cr) = cur_range;
+        // if it is a leaf node
+        if cl == cr {
+            self.tree_arr[tree_index] = inp_arr[cl].clone();
+            return;
+        }
+        let cm = (cl + cr) / 2;
+        self.__build(inp_arr, tree_index * 2 + 1, (cl, cm));
+        self.__build(inp_arr, tree_index * 2 + 2, (cm + 1, cr));
+        self.__pull(tree_index);
+    }
+    /// Recomputes the value of `tree_index` from its two children
+    fn __pull(&mut self, tree_index: usize) {
+        self.tree_arr[tree_index] = self.monoid.combine(
+            &self.tree_arr[tree_index * 2 + 1],
+            &self.tree_arr[tree_index * 2 + 2],
+        );
+    }
+    /// Applies `tag` to the node at `tree_index` covering `len` elements and records it as pending
+    fn __apply(&mut self, tree_index: usize, tag: &T, len: usize) {
+        self.tree_arr[tree_index] = (self.apply_operation)(&self.tree_arr[tree_index], tag, len);
+        self.lazy_arr[tree_index] = match &self.lazy_arr[tree_index] {
+            Some(old) => Some((self.compose_operation)(old, tag)),
+            None => Some(tag.clone()),
+        };
+    }
+    /// Moves the pending tag of `tree_index` down to its two children
+    fn __push(&mut self, tree_index: usize, cur_range: (usize, usize)) {
+        if let Some(tag) = self.lazy_arr[tree_index].take() {
+            let (cl, cr) = cur_range;
+            let cm = (cl + cr) / 2;
+            self.__apply(tree_index * 2 + 1, &tag, cm - cl + 1);
+            self.__apply(tree_index * 2 + 2, &tag, cr - cm);
+        }
+    }
+    /// # Arguments
+    ///
+    /// * `range`: Range of the query, any of `l..r`, `l..=r`, `..r`, `l..` or `..`
+    ///
+    /// returns: M::S, the identity for an empty range
+    ///
+    /// Debug builds panic with a descriptive message if the range is inverted or out of bounds,
+    /// use `try_query` to handle those cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query(0..inp_arr.len()); // query the sum in the range (0, inp_arr.len() - 1) using the query_operation
+    /// ```
+    pub fn query(&mut self, range: impl RangeBounds<usize>) -> M::S {
+        let (start, end) = __bounds(range, self.n).unwrap_or_else(|e| panic!("{}", e));
+        debug_assert!(start <= end, "{}", SegmentTreeError::InvertedRange { start, end });
+        debug_assert!(end <= self.n, "{}", SegmentTreeError::OutOfBounds { end, len: self.n });
+        if start >= end {
+            return self.monoid.identity();
+        }
+        self.__query(0, (start, end - 1), (0, self.n - 1))
+    }
+    /// # Arguments
+    ///
+    /// * `range`: Range of the query
+    ///
+    /// returns: Result<M::S, SegmentTreeError>
+    pub fn try_query(&mut self, range: impl RangeBounds<usize>) -> Result<M::S, SegmentTreeError> {
+        let (start, end) = __checked_bounds(range, self.n)?;
+        if start == end {
+            return Ok(self.monoid.identity());
+        }
+        Ok(self.__query(0, (start, end - 1), (0, self.n - 1)))
+    }
+    /// # Arguments
+    ///
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `range`: Range of the query
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: `M::S`
+    fn __query(
+        &mut self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+    ) -> M::S {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            return self.tree_arr[tree_index].clone();
+        }
+        // if cur_range and range are disjoint, return the identity
+        if cr < l || r < cl {
+            return self.monoid.identity();
+        }
+        // pending tags have to reach the children before we look at them
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        let left_subtree_value = self.__query(tree_index * 2 + 1, range, (cl, cm));
+        let right_subtree_value = self.__query(tree_index * 2 + 2, range, (cm + 1, cr));
+        return self.monoid.combine(&left_subtree_value, &right_subtree_value);
+    }
+    /// # Arguments
+    ///
+    /// * `tag`: Tag to be applied to every value in the range
+    /// * `range`: Range on which the update is to be performed, any of `l..r`, `l..=r`, `..r`, `l..` or `..`
+    ///
+    /// returns: ()
+    ///
+    /// Debug builds panic with a descriptive message if the range is inverted or out of bounds,
+    /// use `try_update` to handle those cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// segment_tree.update(&5, 0..=3); // apply the tag 5 to every value in the range (0, 3) using the apply_operation
+    /// ```
+    pub fn update(
+        &mut self,
+        tag: &T,
+        range: impl RangeBounds<usize>,
+    ) {
+        let (start, end) = __bounds(range, self.n).unwrap_or_else(|e| panic!("{}", e));
+        debug_assert!(start <= end, "{}", SegmentTreeError::InvertedRange { start, end });
+        debug_assert!(end <= self.n, "{}", SegmentTreeError::OutOfBounds { end, len: self.n });
+        if start >= end {
+            return;
+        }
+        self.__update(tag, 0, (start, end - 1), (0, self.n - 1));
+    }
+    /// # Arguments
+    ///
+    /// * `tag`: Tag to be applied to every value in the range
+    /// * `range`: Range on which the update is to be performed
+    ///
+    /// returns: Result<(), SegmentTreeError>, nothing is updated on error
+    pub fn try_update(
+        &mut self,
+        tag: &T,
+        range: impl RangeBounds<usize>,
+    ) -> Result<(), SegmentTreeError> {
+        let (start, end) = __checked_bounds(range, self.n)?;
+        if start < end {
+            self.__update(tag, 0, (start, end - 1), (0, self.n - 1));
+        }
+        Ok(())
+    }
+    /// # Arguments
+    ///
+    /// * `tag`: Tag to be applied
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `range`: Range on which the update is to be performed
+    /// * `cur_range`: Current range of the node in the segment tree
+    ///
+    /// returns: ()
+    fn __update(
+        &mut self,
+        tag: &T,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+    ) {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint
+        if cr < l || r < cl {
+            return;
+        }
+        // if cur_range is inside range, tag the node and stop here
+        if l <= cl && cr <= r {
+            self.__apply(tree_index, tag, cr - cl + 1);
+            return;
+        }
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        self.__update(tag, tree_index * 2 + 1, range, (cl, cm));
+        self.__update(tag, tree_index * 2 + 2, range, (cm + 1, cr));
+        self.__pull(tree_index);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::monoid::Min;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_range_add_range_sum() {
+        let inp_arr: Vec<i64> = vec![1, 2, 3, 4, 5];
+        let mut segment_tree = LazySegmentTree::new(
+            &inp_arr,
+            |a: &i64, b: &i64| a + b,
+            |old: &i64, new: &i64| old + new,
+            |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+            0,
+        );
+        assert_eq!(segment_tree.query(0..=4), 15);
+        segment_tree.update(&10, 1..=3);
+        assert_eq!(segment_tree.query(0..=4), 45);
+        assert_eq!(segment_tree.query(0..=1), 13);
+        assert_eq!(segment_tree.query(3..=4), 19);
+        assert_eq!(segment_tree.query(2..=2), 13);
+    }
+
+    #[test]
+    fn test_range_assign_range_min() {
+        let inp_arr: Vec<i64> = vec![5, 3, 8, 6];
+        let mut segment_tree = LazySegmentTree::with_monoid(
+            &inp_arr,
+            Min::<i64>::new(),
+            |_old: &i64, new: &i64| *new,
+            |_a: &i64, tag: &i64, _len: usize| *tag,
+        );
+        assert_eq!(segment_tree.query(0..=3), 3);
+        segment_tree.update(&7, 0..=1);
+        assert_eq!(segment_tree.query(0..=3), 6);
+        segment_tree.update(&1, 3..=3);
+        assert_eq!(segment_tree.query(0..=2), 7);
+        assert_eq!(segment_tree.query(2..=3), 1);
+    }
+
+    fn range_add_range_sum_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut brute = a.clone();
+        let mut segment_tree = LazySegmentTree::new(
+            &a,
+            |a: &i64, b: &i64| a + b,
+            |old: &i64, new: &i64| old + new,
+            |a: &i64, tag: &i64, len: usize| a + tag * len as i64,
+            0,
+        );
+        for (x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            if v % 2 == 0 {
+                segment_tree.update(&v, l..=r);
+                for item in brute.iter_mut().take(r + 1).skip(l) {
+                    *item += v;
+                }
+            } else {
+                assert_eq!(segment_tree.query(l..=r), brute[l..=r].iter().sum::<i64>());
+            }
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn range_add_range_sum(
+            a in vec(-1000i64..=1000, 1..=100).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            range_add_range_sum_prop(a, ops);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+#![allow(dead_code)]
+use crate::binary_search::lower_bound::LowerBound;
+use crate::binary_search::upper_bound::UpperBound;
+
+/*
+    Merge Sort Tree
+    --------------------------------------------------------------
+    Segment tree whose every node keeps the sorted elements of its
+    range, i.e. the intermediate arrays of a merge sort.
+    Build: O(n log n), Count: O(log^2 n), K-th: O(log^3 n)
+    Memory: O(n log n)
+    --------------------------------------------------------------
+ */
+pub struct MergeSortTree<T: Ord + Clone> {
+    n: usize,
+    tree_arr: Vec<Vec<T>>,
+}
+
+impl<T: Ord + Clone> MergeSortTree<T> {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the merge sort tree is built
+    ///
+    /// returns: MergeSortTree<T>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let merge_sort_tree = MergeSortTree::new(&vec![5, 1, 4, 2, 3]);
+    /// let count = merge_sort_tree.count_less_than(1, 3, &4); // 2, the elements 1 and 2
+    /// let kth = merge_sort_tree.kth_smallest(0, 4, 1); // 2
+    /// ```
+    pub fn new(inp_arr: &Vec<T>) -> Self {
+        let n = inp_arr.len();
+        let mut a = MergeSortTree {
+            n,
+            tree_arr: vec![Vec::new(); 4 * n],
+        };
+        a.__build(inp_arr, 0, (0, n - 1));
+        return a;
+    }
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Input array on which the merge sort tree is built
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: ()
+    fn __build(
+        &mut self,
+        inp_arr: &Vec<T>,
+        tree_index: usize,
+        cur_range: (usize, usize),
+    ) {
+        let (cl, cr) = cur_range;
+        // if it is a leaf node
+        if cl == cr {
+            self.tree_arr[tree_index] = vec![inp_arr[cl].clone()];
+            return;
+        }
+        let cm = (cl + cr) / 2;
+        self.__build(inp_arr, tree_index * 2 + 1, (cl, cm));
+        self.__build(inp_arr, tree_index * 2 + 2, (cm + 1, cr));
+
+        // merge the sorted left and right subtree values
+        let left = &self.tree_arr[tree_index * 2 + 1];
+        let right = &self.tree_arr[tree_index * 2 + 2];
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+        let (mut i, mut j) = (0, 0);
+        while i < left.len() && j < right.len() {
+            if left[i] <= right[j] {
+                merged.push(left[i].clone());
+                i += 1;
+            } else {
+                merged.push(right[j].clone());
+                j += 1;
+            }
+        }
+        merged.extend_from_slice(&left[i..]);
+        merged.extend_from_slice(&right[j..]);
+        self.tree_arr[tree_index] = merged;
+    }
+    /// # Arguments
+    ///
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `range`: Range of the query
+    /// * `cur_range`: Range of the current node in the segment tree
+    /// * `count`: Number of elements to take from a sorted node that lies inside the range
+    ///
+    /// returns: usize
+    fn __count<F>(
+        &self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+        count: &F,
+    ) -> usize
+        where
+            F: Fn(&[T]) -> usize {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            return count(&self.tree_arr[tree_index]);
+        }
+        // if cur_range and range are disjoint
+        if cr < l || r < cl {
+            return 0;
+        }
+        let cm = (cl + cr) / 2;
+        return self.__count(tree_index * 2 + 1, range, (cl, cm), count)
+            + self.__count(tree_index * 2 + 2, range, (cm + 1, cr), count);
+    }
+    /// returns: the number of `i` in `l..=r` such that `a[i] < x`
+    pub fn count_less_than(&self, l: usize, r: usize, x: &T) -> usize {
+        self.__count(0, (l, r), (0, self.n - 1), &|sorted: &[T]| {
+            sorted.lower_bound(x).unwrap_or_else(|i| i)
+        })
+    }
+    /// returns: the number of `i` in `l..=r` such that `a[i] <= x`
+    pub fn count_less_or_equal(&self, l: usize, r: usize, x: &T) -> usize {
+        self.__count(0, (l, r), (0, self.n - 1), &|sorted: &[T]| {
+            sorted.upper_bound(x).unwrap_or_else(|i| i)
+        })
+    }
+    /// returns: the number of `i` in `l..=r` such that `low <= a[i] <= high`
+    pub fn count_in_value_range(&self, l: usize, r: usize, low: &T, high: &T) -> usize {
+        if low > high {
+            return 0;
+        }
+        self.count_less_or_equal(l, r, high) - self.count_less_than(l, r, low)
+    }
+    /// returns: the `k`-th (0-indexed) smallest element of `a[l..=r]`, `k <= r - l`
+    pub fn kth_smallest(&self, l: usize, r: usize, k: usize) -> T {
+        // binary search on the sorted values of the root for the first one with more than k elements <= it
+        let sorted = &self.tree_arr[0];
+        let mut low = 0;
+        let mut high = self.n - 1;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.count_less_or_equal(l, r, &sorted[mid]) > k {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        sorted[low].clone()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_merge_sort_tree() {
+        let merge_sort_tree = MergeSortTree::new(&vec![5, 1, 4, 2, 3]);
+        assert_eq!(merge_sort_tree.count_less_than(1, 3, &4), 2);
+        assert_eq!(merge_sort_tree.count_less_or_equal(1, 3, &4), 3);
+        assert_eq!(merge_sort_tree.count_in_value_range(0, 4, &2, &4), 3);
+        assert_eq!(merge_sort_tree.kth_smallest(0, 4, 1), 2);
+        assert_eq!(merge_sort_tree.kth_smallest(0, 2, 2), 5);
+    }
+
+    fn count_and_kth_prop(a: Vec<i32>, queries: Vec<(usize, usize, i32, usize)>) {
+        let n = a.len();
+        let merge_sort_tree = MergeSortTree::new(&a);
+        for (x, y, v, k) in queries {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            assert_eq!(merge_sort_tree.count_less_than(l, r, &v), a[l..=r].iter().filter(|&&e| e < v).count());
+            assert_eq!(merge_sort_tree.count_less_or_equal(l, r, &v), a[l..=r].iter().filter(|&&e| e <= v).count());
+            let mut sorted = a[l..=r].to_vec();
+            sorted.sort();
+            let k = k % (r - l + 1);
+            assert_eq!(merge_sort_tree.kth_smallest(l, r, k), sorted[k]);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn count_and_kth(
+            a in vec(0..=100, 1..=100).boxed(),
+            queries in vec((0usize..100, 0usize..100, 0..=100, 0usize..100), 1..=100).boxed(),
+        ) {
+            count_and_kth_prop(a, queries);
+        }
+    }
+}
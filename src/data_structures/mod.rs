@@ -1,2 +1,15 @@
+pub mod monoid;
 pub mod segment_tree;
-pub mod trie;
\ No newline at end of file
+pub mod fenwick_tree;
+pub mod lazy_segment_tree;
+pub mod iterative_segment_tree;
+pub mod persistent_segment_tree;
+pub mod dynamic_segment_tree;
+pub mod segment_tree_beats;
+pub mod segment_tree_2d;
+pub mod merge_sort_tree;
+pub mod trie;
+pub mod array_trie;
+pub mod binary_trie;
+pub mod persistent_binary_trie;
+pub mod aho_corasick;
\ No newline at end of file
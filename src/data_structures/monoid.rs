@@ -0,0 +1,192 @@
+#![allow(dead_code)]
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+
+/// An associative operation `combine` together with its `identity` element.
+///
+/// Structures such as `SegmentTree` are generic over this trait so the aggregate can be
+/// named in a type (`SegmentTree<Sum<i64>>`) instead of being re-declared at every call site.
+pub trait Monoid {
+    type S: Clone;
+    /// returns: the value `e` such that `combine(e, x) == combine(x, e) == x`
+    fn identity(&self) -> Self::S;
+    /// returns: `a · b`, it must be associative but need not be commutative
+    fn combine(&self, a: &Self::S, b: &Self::S) -> Self::S;
+}
+
+/// Adapter that turns an operation closure and its fallback value into a `Monoid`
+pub struct FnMonoid<A, F>
+    where
+        F: Fn(&A, &A) -> A {
+    operation: F,
+    identity: A,
+}
+
+impl<A: Clone, F> FnMonoid<A, F>
+    where
+        F: Fn(&A, &A) -> A {
+    pub fn new(operation: F, identity: A) -> Self {
+        FnMonoid { operation, identity }
+    }
+}
+
+impl<A: Clone, F> Monoid for FnMonoid<A, F>
+    where
+        F: Fn(&A, &A) -> A {
+    type S = A;
+    fn identity(&self) -> A {
+        self.identity.clone()
+    }
+    fn combine(&self, a: &A, b: &A) -> A {
+        (self.operation)(a, b)
+    }
+}
+
+macro_rules! marker_monoid {
+    ($($name:ident),*) => {
+        $(
+            pub struct $name<T>(PhantomData<T>);
+
+            impl<T> $name<T> {
+                pub fn new() -> Self {
+                    $name(PhantomData)
+                }
+            }
+
+            impl<T> Default for $name<T> {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        )*
+    };
+}
+
+marker_monoid!(Sum, Min, Max, Xor, Gcd, Affine);
+
+/// `a + b` with identity `T::default()`
+impl<T: Clone + Default + Add<Output=T>> Monoid for Sum<T> {
+    type S = T;
+    fn identity(&self) -> T {
+        T::default()
+    }
+    fn combine(&self, a: &T, b: &T) -> T {
+        a.clone() + b.clone()
+    }
+}
+
+/// Composition of affine maps `(a, b): x -> a * x + b`.
+/// `combine(f, g)` is the map that applies `f` first and then `g`.
+impl<T: Clone + Add<Output=T> + Mul<Output=T> + From<u8>> Monoid for Affine<T> {
+    type S = (T, T);
+    fn identity(&self) -> (T, T) {
+        (T::from(1), T::from(0))
+    }
+    fn combine(&self, f: &(T, T), g: &(T, T)) -> (T, T) {
+        (
+            g.0.clone() * f.0.clone(),
+            g.0.clone() * f.1.clone() + g.1.clone(),
+        )
+    }
+}
+
+impl<T: Clone + Add<Output=T> + Mul<Output=T> + From<u8>> Affine<T> {
+    /// returns: `a * x + b` for the map `f = (a, b)`
+    pub fn apply(f: &(T, T), x: &T) -> T {
+        f.0.clone() * x.clone() + f.1.clone()
+    }
+}
+
+macro_rules! integer_monoids {
+    ($($t:ty),*) => {
+        $(
+            impl Monoid for Min<$t> {
+                type S = $t;
+                fn identity(&self) -> $t {
+                    <$t>::MAX
+                }
+                fn combine(&self, a: &$t, b: &$t) -> $t {
+                    *a.min(b)
+                }
+            }
+
+            impl Monoid for Max<$t> {
+                type S = $t;
+                fn identity(&self) -> $t {
+                    <$t>::MIN
+                }
+                fn combine(&self, a: &$t, b: &$t) -> $t {
+                    *a.max(b)
+                }
+            }
+
+            impl Monoid for Xor<$t> {
+                type S = $t;
+                fn identity(&self) -> $t {
+                    0
+                }
+                fn combine(&self, a: &$t, b: &$t) -> $t {
+                    a ^ b
+                }
+            }
+
+            /// gcd with identity `0`, the result is always non-negative
+            impl Monoid for Gcd<$t> {
+                type S = $t;
+                fn identity(&self) -> $t {
+                    0
+                }
+                #[allow(unused_comparisons)]
+                fn combine(&self, a: &$t, b: &$t) -> $t {
+                    let (mut a, mut b) = (*a, *b);
+                    while b != 0 {
+                        (a, b) = (b, a % b);
+                    }
+                    if a < 0 { a.wrapping_neg() } else { a }
+                }
+            }
+        )*
+    };
+}
+
+integer_monoids!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_monoids() {
+        assert_eq!(Sum::<i64>::new().combine(&3, &4), 7);
+        assert_eq!(Sum::<i64>::new().identity(), 0);
+        assert_eq!(Min::<i32>::new().combine(&3, &-4), -4);
+        assert_eq!(Min::<i32>::new().identity(), i32::MAX);
+        assert_eq!(Max::<u64>::new().combine(&3, &4), 4);
+        assert_eq!(Max::<u64>::new().identity(), 0);
+        assert_eq!(Xor::<u32>::new().combine(&0b1100, &0b1010), 0b0110);
+        assert_eq!(Gcd::<i64>::new().combine(&-12, &18), 6);
+        assert_eq!(Gcd::<u64>::new().combine(&0, &18), 18);
+        assert_eq!(Gcd::<u64>::new().combine(&7, &0), 7);
+    }
+
+    #[test]
+    fn test_affine_composition() {
+        let monoid = Affine::<i64>::new();
+        let f = (2, 3); // 2x + 3
+        let g = (5, -1); // 5x - 1
+        let fg = monoid.combine(&f, &g);
+        for x in -10..=10 {
+            assert_eq!(Affine::apply(&fg, &x), Affine::apply(&g, &Affine::apply(&f, &x)));
+        }
+        assert_eq!(monoid.combine(&monoid.identity(), &f), f);
+        assert_eq!(monoid.combine(&f, &monoid.identity()), f);
+    }
+
+    #[test]
+    fn test_fn_monoid() {
+        let monoid = FnMonoid::new(|a: &String, b: &String| format!("{}{}", a, b), String::new());
+        assert_eq!(monoid.combine(&"ab".to_string(), &"cd".to_string()), "abcd");
+        assert_eq!(monoid.identity(), "");
+    }
+}
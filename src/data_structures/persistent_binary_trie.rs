@@ -0,0 +1,182 @@
+#![allow(dead_code)]
+
+/*
+    Persistent Binary Trie
+    --------------------------------------------------------------
+    BinaryTrie where every insertion copies the BITS + 1 nodes on
+    the path of the key and returns a new version, untouched
+    subtrees are shared. Counts of two versions can be subtracted,
+    so with version i holding a[0..i] the keys of a[l..=r] are
+    "version r + 1 minus version l".
+    Nodes are rows of a flat `Vec<[u32; 2]>` arena, node `0` is the
+    empty trie (both children point back to it, count 0).
+    Insert: O(BITS) time and memory, Query: O(BITS)
+    --------------------------------------------------------------
+ */
+pub struct PersistentBinaryTrie<const BITS: u32> {
+    next: Vec<[u32; 2]>,
+    count: Vec<u32>,
+    roots: Vec<u32>,
+}
+
+impl<const BITS: u32> Default for PersistentBinaryTrie<BITS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const BITS: u32> PersistentBinaryTrie<BITS> {
+    /// returns: PersistentBinaryTrie whose only version `0` is empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let a = [3u64, 10, 6];
+    /// let mut trie = PersistentBinaryTrie::<32>::new();
+    /// for &x in &a {
+    ///     trie.insert(trie.versions() - 1, x); // version i + 1 holds a[0..=i]
+    /// }
+    /// let best = trie.max_xor(1, 3, 5); // max(10 ^ 5, 6 ^ 5) over a[1..=2] = 15
+    /// ```
+    pub fn new() -> Self {
+        assert!((1..=64).contains(&BITS), "PersistentBinaryTrie supports 1 to 64 bits, got {}", BITS);
+        PersistentBinaryTrie {
+            next: vec![[0; 2]],
+            count: vec![0],
+            roots: vec![0],
+        }
+    }
+
+    /// Reserves arena space for `inserts` more insertions so the node pool is allocated once
+    pub fn reserve(&mut self, inserts: usize) {
+        self.next.reserve(inserts * (BITS as usize + 1));
+        self.count.reserve(inserts * (BITS as usize + 1));
+        self.roots.reserve(inserts);
+    }
+
+    /// returns: the number of versions, version `0` is the empty trie
+    pub fn versions(&self) -> usize {
+        self.roots.len()
+    }
+
+    fn __bit(x: u64, i: u32) -> usize {
+        ((x >> i) & 1) as usize
+    }
+
+    /// returns: the index of a copy of `node` with one more key
+    fn __copy_with_one_more(&mut self, node: u32) -> u32 {
+        self.next.push(self.next[node as usize]);
+        self.count.push(self.count[node as usize] + 1);
+        (self.next.len() - 1) as u32
+    }
+
+    /// Inserts `x` on top of `version`, which is left unchanged
+    ///
+    /// returns: usize, the newly created version
+    pub fn insert(&mut self, version: usize, x: u64) -> usize {
+        debug_assert!(BITS == 64 || x >> BITS == 0, "key {} does not fit in {} bits", x, BITS);
+        let root = self.__copy_with_one_more(self.roots[version]);
+        let mut current_node = root;
+        for i in (0..BITS).rev() {
+            let b = Self::__bit(x, i);
+            let child = self.__copy_with_one_more(self.next[current_node as usize][b]);
+            self.next[current_node as usize][b] = child;
+            current_node = child;
+        }
+        self.roots.push(root);
+        self.roots.len() - 1
+    }
+
+    /// returns: the number of keys in `new_version` that are not in `old_version`
+    pub fn len_between(&self, old_version: usize, new_version: usize) -> usize {
+        (self.count[self.roots[new_version] as usize] - self.count[self.roots[old_version] as usize]) as usize
+    }
+
+    /// Walks down both versions, at each bit taking the child `bit of x ^ maximize` when the difference of the
+    /// two versions holds a key there and the other child otherwise
+    fn __descend(&self, old_version: usize, new_version: usize, x: u64, maximize: bool) -> Option<u64> {
+        if self.len_between(old_version, new_version) == 0 {
+            return None;
+        }
+        let mut old_node = self.roots[old_version] as usize;
+        let mut new_node = self.roots[new_version] as usize;
+        let mut result = 0;
+        for i in (0..BITS).rev() {
+            let preferred = Self::__bit(x, i) ^ maximize as usize;
+            let (old_child, new_child) = (self.next[old_node][preferred] as usize, self.next[new_node][preferred] as usize);
+            let side = if self.count[new_child] > self.count[old_child] { preferred } else { preferred ^ 1 };
+            if side != Self::__bit(x, i) {
+                result |= 1 << i;
+            }
+            old_node = self.next[old_node][side] as usize;
+            new_node = self.next[new_node][side] as usize;
+        }
+        Some(result)
+    }
+
+    /// returns: `max(a ^ x)` over the keys `a` in `new_version` but not in `old_version`, None if there are none
+    pub fn max_xor(&self, old_version: usize, new_version: usize, x: u64) -> Option<u64> {
+        self.__descend(old_version, new_version, x, true)
+    }
+
+    /// returns: `min(a ^ x)` over the keys `a` in `new_version` but not in `old_version`, None if there are none
+    pub fn min_xor(&self, old_version: usize, new_version: usize, x: u64) -> Option<u64> {
+        self.__descend(old_version, new_version, x, false)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_persistent_binary_trie() {
+        let a = [3u64, 10, 6];
+        let mut trie = PersistentBinaryTrie::<32>::new();
+        trie.reserve(a.len());
+        for &x in &a {
+            trie.insert(trie.versions() - 1, x);
+        }
+        assert_eq!(trie.max_xor(1, 3, 5), Some(15));
+        assert_eq!(trie.max_xor(0, 1, 5), Some(6));
+        assert_eq!(trie.min_xor(0, 3, 5), Some(3));
+        assert_eq!(trie.max_xor(2, 2, 5), None);
+        assert_eq!(trie.len_between(1, 3), 2);
+        // branching off an old version leaves the newer ones intact
+        let branch = trie.insert(1, 5);
+        assert_eq!(trie.max_xor(0, branch, 5), Some(6));
+        assert_eq!(trie.min_xor(0, branch, 5), Some(0));
+        assert_eq!(trie.min_xor(0, 3, 5), Some(3));
+    }
+
+    fn range_xor_prop(a: Vec<u64>, queries: Vec<(usize, usize, u64)>) {
+        let n = a.len();
+        let mut trie = PersistentBinaryTrie::<10>::new();
+        for &x in &a {
+            trie.insert(trie.versions() - 1, x);
+        }
+        for (x, y, v) in queries {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            assert_eq!(trie.max_xor(l, r + 1, v), a[l..=r].iter().map(|&e| e ^ v).max());
+            assert_eq!(trie.min_xor(l, r + 1, v), a[l..=r].iter().map(|&e| e ^ v).min());
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn range_xor(
+            a in vec(0u64..1024, 1..=100).boxed(),
+            queries in vec((0usize..100, 0usize..100, 0u64..1024), 1..=100).boxed(),
+        ) {
+            range_xor_prop(a, queries);
+        }
+    }
+}
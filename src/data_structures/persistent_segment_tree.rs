@@ -0,0 +1,381 @@
+#![allow(dead_code)]
+use crate::data_structures::monoid::{FnMonoid, Monoid, Sum};
+
+/*
+    Persistent Segment Tree
+    --------------------------------------------------------------
+    Every point update copies the O(log n) nodes on the path to the
+    leaf and returns a new version, the untouched subtrees are shared
+    with the previous version. All nodes live in one arena (`Vec`)
+    and refer to their children by `u32` index.
+    Build: O(n), Query: O(log n), Update: O(log n) time and memory
+    --------------------------------------------------------------
+ */
+struct Node<S> {
+    left: u32,
+    right: u32,
+    value: S,
+}
+
+pub struct PersistentSegmentTree<M, NO = fn(&<M as Monoid>::S, &<M as Monoid>::S) -> <M as Monoid>::S>
+    where
+        M: Monoid,
+        NO: Fn(&M::S, &M::S) -> M::S {
+    n: usize,
+    nodes: Vec<Node<M::S>>,
+    roots: Vec<u32>,
+    monoid: M,
+    node_operation: NO,
+}
+
+impl<A: Clone, QO, NO> PersistentSegmentTree<FnMonoid<A, QO>, NO>
+    where
+        QO: Fn(&A, &A) -> A,
+        NO: Fn(&A, &A) -> A {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which version `0` of the segment tree is built
+    /// * `query_operation`: It is the operation that is performed on the left and right subtree values to get the current node value
+    /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
+    /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
+    ///
+    /// returns: PersistentSegmentTree<FnMonoid<A, QO>, NO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let inp_arr: Vec<i64> = vec![0; 4];
+    /// let mut segment_tree = PersistentSegmentTree::new(
+    ///    &inp_arr,
+    ///   |a: &i64, b: &i64| a + b,
+    ///   |_: &i64, b: &i64| *b,
+    ///   0,
+    /// );
+    /// let v1 = segment_tree.update(0, &5, 2); // a[2] = 5 on top of version 0
+    /// let old = segment_tree.query(0, (0, 3)); // 0
+    /// let new = segment_tree.query(v1, (0, 3)); // 5
+    /// ```
+    pub fn new(
+        inp_arr: &Vec<A>,
+        query_operation: QO,
+        node_operation: NO,
+        fallback_value: A,
+    ) -> Self {
+        PersistentSegmentTree::with_monoid(inp_arr, FnMonoid::new(query_operation, fallback_value), node_operation)
+    }
+}
+
+impl<M: Monoid> PersistentSegmentTree<M> {
+    /// Builds a persistent segment tree whose `update` assigns the new value to the leaf
+    ///
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which version `0` of the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values
+    ///
+    /// returns: PersistentSegmentTree<M>
+    pub fn from_monoid(inp_arr: &Vec<M::S>, monoid: M) -> Self {
+        PersistentSegmentTree::with_monoid(inp_arr, monoid, |_: &M::S, b: &M::S| b.clone())
+    }
+}
+
+impl<M: Monoid, NO> PersistentSegmentTree<M, NO>
+    where
+        NO: Fn(&M::S, &M::S) -> M::S {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which version `0` of the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values, its identity is returned for disjoint ranges
+    /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
+    ///
+    /// returns: PersistentSegmentTree<M, NO>
+    pub fn with_monoid(
+        inp_arr: &Vec<M::S>,
+        monoid: M,
+        node_operation: NO,
+    ) -> Self {
+        let n = inp_arr.len();
+        let mut a = PersistentSegmentTree {
+            n,
+            nodes: Vec::with_capacity(2 * n),
+            roots: Vec::new(),
+            monoid,
+            node_operation,
+        };
+        let root = a.__build(inp_arr, (0, n - 1));
+        a.roots.push(root);
+        return a;
+    }
+    /// Reserves arena space for `updates` more updates so the node pool is allocated once
+    ///
+    /// # Arguments
+    ///
+    /// * `updates`: Number of updates that will be performed
+    ///
+    /// returns: ()
+    pub fn reserve(&mut self, updates: usize) {
+        let depth = (usize::BITS - self.n.leading_zeros()) as usize + 1;
+        self.nodes.reserve(updates * depth);
+        self.roots.reserve(updates);
+    }
+    /// returns: the number of versions, version `0` is the one built from `inp_arr`
+    pub fn versions(&self) -> usize {
+        self.roots.len()
+    }
+    fn __push_node(&mut self, left: u32, right: u32, value: M::S) -> u32 {
+        self.nodes.push(Node { left, right, value });
+        (self.nodes.len() - 1) as u32
+    }
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Input array on which the segment tree is built
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: u32, index of the created node in the arena
+    fn __build(
+        &mut self,
+        inp_arr: &Vec<M::S>,
+        cur_range: (usize, usize),
+    ) -> u32 {
+        let (cl, cr) = cur_range;
+        // if it is a leaf node
+        if cl == cr {
+            return self.__push_node(0, 0, inp_arr[cl].clone());
+        }
+        let cm = (cl + cr) / 2;
+        let left = self.__build(inp_arr, (cl, cm));
+        let right = self.__build(inp_arr, (cm + 1, cr));
+        let value = self.monoid.combine(&self.nodes[left as usize].value, &self.nodes[right as usize].value);
+        self.__push_node(left, right, value)
+    }
+    /// # Arguments
+    ///
+    /// * `version`: Version to be queried
+    /// * `range`: Range of the query
+    ///
+    /// returns: M::S
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query(v1, (0, inp_arr.len() - 1)); // query the sum in the range (0, inp_arr.len() - 1) as of version v1
+    /// ```
+    pub fn query(&self, version: usize, range: (usize, usize)) -> M::S {
+        self.__query(self.roots[version], range, (0, self.n - 1))
+    }
+    /// # Arguments
+    ///
+    /// * `node`: Index of the current node in the arena
+    /// * `range`: Range of the query
+    /// * `cur_range`: Range of the current node in the segment tree
+    ///
+    /// returns: `M::S`
+    fn __query(
+        &self,
+        node: u32,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+    ) -> M::S {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        let cur = &self.nodes[node as usize];
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            return cur.value.clone();
+        }
+        // if cur_range and range are disjoint, return the identity
+        if cr < l || r < cl {
+            return self.monoid.identity();
+        }
+        let cm = (cl + cr) / 2;
+        let left_subtree_value = self.__query(cur.left, range, (cl, cm));
+        let right_subtree_value = self.__query(cur.right, range, (cm + 1, cr));
+        return self.monoid.combine(&left_subtree_value, &right_subtree_value);
+    }
+    /// # Arguments
+    ///
+    /// * `version`: Version on top of which the update is performed, it is left unchanged
+    /// * `value`: New Value to be updated
+    /// * `index`: Index of the element to be updated
+    ///
+    /// returns: usize, the newly created version
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let v2 = segment_tree.update(v1, &3, 0); // apply the node_operation with 3 to a[0] of version v1
+    /// ```
+    pub fn update(
+        &mut self,
+        version: usize,
+        value: &M::S,
+        index: usize,
+    ) -> usize {
+        let root = self.__update(self.roots[version], value, index, (0, self.n - 1));
+        self.roots.push(root);
+        self.roots.len() - 1
+    }
+    /// # Arguments
+    ///
+    /// * `node`: Index of the current node in the arena
+    /// * `value`: New Value to be updated
+    /// * `index`: Index of the element to be updated
+    /// * `cur_range`: Current range of the node in the segment tree
+    ///
+    /// returns: u32, index of the copy of `node` in the arena
+    fn __update(
+        &mut self,
+        node: u32,
+        value: &M::S,
+        index: usize,
+        cur_range: (usize, usize),
+    ) -> u32 {
+        let (cl, cr) = cur_range;
+        // when we reach the leaf level
+        if cl == cr {
+            let new_value = (self.node_operation)(&self.nodes[node as usize].value, value);
+            return self.__push_node(0, 0, new_value);
+        }
+        let cm = (cl + cr) / 2;
+        let (mut left, mut right) = (self.nodes[node as usize].left, self.nodes[node as usize].right);
+        if index <= cm {
+            left = self.__update(left, value, index, (cl, cm));
+        } else {
+            right = self.__update(right, value, index, (cm + 1, cr));
+        }
+        let new_value = self.monoid.combine(&self.nodes[left as usize].value, &self.nodes[right as usize].value);
+        self.__push_node(left, right, new_value)
+    }
+}
+
+impl<NO> PersistentSegmentTree<Sum<usize>, NO>
+    where
+        NO: Fn(&usize, &usize) -> usize {
+    /// Finds the k-th smallest element among the values inserted between two versions of a counting tree
+    ///
+    /// Build the tree over counts of (compressed) values, insert `a[i]` as version `i + 1`,
+    /// then the elements of `a[l..=r]` are the difference of versions `r + 1` and `l`.
+    ///
+    /// # Arguments
+    ///
+    /// * `old_version`: Version whose counts are subtracted
+    /// * `new_version`: Version whose counts are used
+    /// * `k`: 0-indexed rank, `k < query(new_version) - query(old_version)` over the full range
+    ///
+    /// returns: usize, the (compressed) value with rank `k`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let a = vec![2, 0, 3, 1]; // already compressed to [0, 4)
+    /// let mut segment_tree = PersistentSegmentTree::with_monoid(&vec![0; 4], Sum::new(), |a: &usize, b: &usize| a + b);
+    /// for &x in &a {
+    ///     segment_tree.update(segment_tree.versions() - 1, &1, x);
+    /// }
+    /// let median = segment_tree.kth(1, 4, 1); // a[1..=3] = [0, 3, 1], the second smallest is 1
+    /// ```
+    pub fn kth(&self, old_version: usize, new_version: usize, k: usize) -> usize {
+        let mut old_node = self.roots[old_version] as usize;
+        let mut new_node = self.roots[new_version] as usize;
+        let (mut cl, mut cr) = (0, self.n - 1);
+        let mut k = k;
+        while cl < cr {
+            let cm = (cl + cr) / 2;
+            let old_left = self.nodes[old_node].left as usize;
+            let new_left = self.nodes[new_node].left as usize;
+            let left_count = self.nodes[new_left].value - self.nodes[old_left].value;
+            if k < left_count {
+                old_node = old_left;
+                new_node = new_left;
+                cr = cm;
+            } else {
+                k -= left_count;
+                old_node = self.nodes[old_node].right as usize;
+                new_node = self.nodes[new_node].right as usize;
+                cl = cm + 1;
+            }
+        }
+        cl
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_versions_are_independent() {
+        let inp_arr: Vec<i64> = vec![1, 2, 3, 4];
+        let mut segment_tree = PersistentSegmentTree::from_monoid(&inp_arr, Sum::<i64>::new());
+        segment_tree.reserve(3);
+        let v1 = segment_tree.update(0, &10, 0);
+        let v2 = segment_tree.update(v1, &20, 3);
+        let v3 = segment_tree.update(0, &0, 1);
+        assert_eq!(segment_tree.versions(), 4);
+        assert_eq!(segment_tree.query(0, (0, 3)), 10);
+        assert_eq!(segment_tree.query(v1, (0, 3)), 19);
+        assert_eq!(segment_tree.query(v2, (0, 3)), 35);
+        assert_eq!(segment_tree.query(v2, (1, 2)), 5);
+        assert_eq!(segment_tree.query(v3, (0, 3)), 8);
+        assert_eq!(segment_tree.query(v3, (0, 0)), 1);
+    }
+
+    fn kth_prop(a: Vec<usize>, queries: Vec<(usize, usize, usize)>) {
+        let n = a.len();
+        let mut segment_tree = PersistentSegmentTree::with_monoid(&vec![0; 100], Sum::new(), |a: &usize, b: &usize| a + b);
+        segment_tree.reserve(n);
+        for &x in &a {
+            segment_tree.update(segment_tree.versions() - 1, &1, x);
+        }
+        for (x, y, k) in queries {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            let k = k % (r - l + 1);
+            let mut sorted = a[l..=r].to_vec();
+            sorted.sort();
+            assert_eq!(segment_tree.kth(l, r + 1, k), sorted[k]);
+        }
+    }
+
+    fn history_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut segment_tree = PersistentSegmentTree::new(&a, |a: &i64, b: &i64| *a.min(b), |_: &i64, b: &i64| *b, i64::MAX);
+        let mut history = vec![a.clone()];
+        for (version, i, v) in ops {
+            let version = version % history.len();
+            let mut next = history[version].clone();
+            next[i % n] = v;
+            history.push(next);
+            assert_eq!(segment_tree.update(version, &v, i % n), history.len() - 1);
+        }
+        for (version, arr) in history.iter().enumerate() {
+            for l in 0..n {
+                assert_eq!(segment_tree.query(version, (l, n - 1)), *arr[l..].iter().min().unwrap());
+            }
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn kth(
+            a in vec(0usize..100, 1..=100).boxed(),
+            queries in vec((0usize..100, 0usize..100, 0usize..100), 1..=100).boxed(),
+        ) {
+            kth_prop(a, queries);
+        }
+        #[test]
+        fn history(
+            a in vec(-1000i64..=1000, 1..=30).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=30).boxed(),
+        ) {
+            history_prop(a, ops);
+        }
+    }
+}
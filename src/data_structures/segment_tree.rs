@@ -1,16 +1,72 @@
 #![allow(dead_code)]
-pub struct SegmentTree<A, QO, NO>
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+use crate::data_structures::monoid::{FnMonoid, Monoid};
+
+/// Reason a `try_query` / `try_update` range was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentTreeError {
+    /// the range starts after it ends, e.g. `5..3`
+    InvertedRange { start: usize, end: usize },
+    /// the range ends past the last element, `end` is exclusive
+    OutOfBounds { end: usize, len: usize },
+}
+
+impl fmt::Display for SegmentTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SegmentTreeError::InvertedRange { start, end } => {
+                write!(f, "segment tree range starts at {} but ends at {}", start, end)
+            }
+            SegmentTreeError::OutOfBounds { end, len } => {
+                write!(f, "segment tree range end {} is out of bounds for length {}", end, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SegmentTreeError {}
+
+/// Converts `range` over `len` elements to the half-open `(start, end)`, only failing when a bound does not fit
+/// in usize, shared by the segment trees that take `RangeBounds`
+pub(super) fn __bounds(range: impl RangeBounds<usize>, len: usize) -> Result<(usize, usize), SegmentTreeError> {
+    let overflow = SegmentTreeError::OutOfBounds { end: usize::MAX, len };
+    let start = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l.checked_add(1).ok_or(overflow.clone())?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&r) => r.checked_add(1).ok_or(overflow)?,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => len,
+    };
+    Ok((start, end))
+}
+
+/// Converts `range` to the half-open `(start, end)` if it lies inside `0..len`
+pub(super) fn __checked_bounds(range: impl RangeBounds<usize>, len: usize) -> Result<(usize, usize), SegmentTreeError> {
+    let (start, end) = __bounds(range, len)?;
+    if start > end {
+        return Err(SegmentTreeError::InvertedRange { start, end });
+    }
+    if end > len {
+        return Err(SegmentTreeError::OutOfBounds { end, len });
+    }
+    Ok((start, end))
+}
+
+pub struct SegmentTree<M, NO = fn(&<M as Monoid>::S, &<M as Monoid>::S) -> <M as Monoid>::S>
     where
-        QO: Fn(&A, &A) -> A,
-        NO: Fn(&A, &A) -> A {
+        M: Monoid,
+        NO: Fn(&M::S, &M::S) -> M::S {
     n: usize,
-    tree_arr: Vec<A>,
-    query_operation: QO,
+    tree_arr: Vec<M::S>,
+    monoid: M,
     node_operation: NO,
-    fallback_value: A,
 }
 
-impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
+impl<A: Clone, QO, NO> SegmentTree<FnMonoid<A, QO>, NO>
     where
         QO: Fn(&A, &A) -> A,
         NO: Fn(&A, &A) -> A {
@@ -21,7 +77,7 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
     /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
     /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
     ///
-    /// returns: SegmentTree<A, QO, NO>
+    /// returns: SegmentTree<FnMonoid<A, QO>, NO>
     ///
     /// # Examples
     ///
@@ -33,27 +89,77 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
     ///   |a: &i64, b: &i64| a + b,
     ///   i64::MIN,
     /// );
-    /// segment_tree.update(&1, (0, 3)); // update the value at index 0 to 3 using the node_operation
-    /// let max = segment_tree.query((0, inp_arr.len() - 1)); // query the maximum value in the range (0, inp_arr.len() - 1) using the query_operation
+    /// segment_tree.update(&1, 0..=3); // add 1 to the values at index 0 to 3 using the node_operation
+    /// let max = segment_tree.query(..); // query the maximum value of the whole array using the query_operation
     /// ```
     pub fn new(
         inp_arr: &Vec<A>,
         query_operation: QO,
         node_operation: NO,
         fallback_value: A,
+    ) -> Self {
+        SegmentTree::with_monoid(inp_arr, FnMonoid::new(query_operation, fallback_value), node_operation)
+    }
+}
+
+impl<M: Monoid> SegmentTree<M> {
+    /// Builds a segment tree whose `update` assigns the new value to the leaves
+    ///
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values
+    ///
+    /// returns: SegmentTree<M>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let inp_arr: Vec<i64> = vec![3, 1, 4, 1];
+    /// let mut segment_tree: SegmentTree<Sum<i64>> = SegmentTree::from_monoid(&inp_arr, Sum::new());
+    /// segment_tree.update(&5, 1..=1); // a[1] = 5
+    /// let sum = segment_tree.query(0..4); // 13
+    /// ```
+    pub fn from_monoid(inp_arr: &Vec<M::S>, monoid: M) -> Self {
+        SegmentTree::with_monoid(inp_arr, monoid, |_: &M::S, b: &M::S| b.clone())
+    }
+}
+
+impl<M: Monoid, NO> SegmentTree<M, NO>
+    where
+        NO: Fn(&M::S, &M::S) -> M::S {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    /// * `monoid`: Monoid used to combine the left and right subtree values, its identity is returned for disjoint ranges
+    /// * `node_operation`: It is the operation that is performed on the current node value and the value to be updated
+    ///
+    /// returns: SegmentTree<M, NO>
+    pub fn with_monoid(
+        inp_arr: &Vec<M::S>,
+        monoid: M,
+        node_operation: NO,
     ) -> Self {
         let n = inp_arr.len();
-        let tree_arr: Vec<A> = vec![Default::default(); 4 * n];
+        let tree_arr: Vec<M::S> = vec![monoid.identity(); 4 * n];
         let mut a = SegmentTree {
             n,
             tree_arr,
-            query_operation,
+            monoid,
             node_operation,
-            fallback_value,
         };
-        a.__build(inp_arr, 0, (0, n - 1));
+        // an empty tree has no nodes to build, every query on it is the empty range
+        if n > 0 {
+            a.__build(inp_arr, 0, (0, n - 1));
+        }
         return a;
     }
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
     /// # Arguments
     ///
     /// * `inp_arr`: Input array on which the segment tree is built
@@ -63,7 +169,7 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
     /// returns: ()
     fn __build(
         &mut self,
-        inp_arr: &Vec<A>,
+        inp_arr: &Vec<M::S>,
         tree_index: usize,
         cur_range: (usize, usize),
     ) {
@@ -85,21 +191,50 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
         // merge the left and right subtree values to get the current node value
         let left_subtree_value = self.tree_arr[tree_index * 2 + 1].clone();
         let right_subtree_value = self.tree_arr[tree_index * 2 + 2].clone();
-        self.tree_arr[tree_index] = (self.query_operation)(&left_subtree_value, &right_subtree_value);
+        self.tree_arr[tree_index] = self.monoid.combine(&left_subtree_value, &right_subtree_value);
+    }
+    /// # Arguments
+    ///
+    /// * `range`: Range of the query, any of `l..r`, `l..=r`, `..r`, `l..` or `..`
+    ///
+    /// returns: M::S, the identity for an empty range
+    ///
+    /// Debug builds panic with a descriptive message if the range is inverted or out of bounds,
+    /// use `try_query` to handle those cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query(0..inp_arr.len()); // query the maximum value in the range (0, inp_arr.len() - 1) using the query_operation
+    ///  segment_tree.query(..=2); // query the maximum value of the first three elements
+    /// ```
+    pub fn query(&self, range: impl RangeBounds<usize>) -> M::S {
+        let (start, end) = __bounds(range, self.n).unwrap_or_else(|e| panic!("{}", e));
+        debug_assert!(start <= end, "{}", SegmentTreeError::InvertedRange { start, end });
+        debug_assert!(end <= self.n, "{}", SegmentTreeError::OutOfBounds { end, len: self.n });
+        if start >= end {
+            return self.monoid.identity();
+        }
+        self.__query(0, (start, end - 1), (0, self.n - 1))
     }
     /// # Arguments
     ///
     /// * `range`: Range of the query
     ///
-    /// returns: A
+    /// returns: Result<M::S, SegmentTreeError>
     ///
     /// # Examples
     ///
     /// ```
-    ///  segment_tree.query((0, inp_arr.len() - 1)); // query the maximum value in the range (0, inp_arr.len() - 1) using the query_operation
+    ///  assert!(segment_tree.try_query(2..=0).is_err());
+    ///  assert!(segment_tree.try_query(0..=inp_arr.len()).is_err());
     /// ```
-    pub fn query(&self, range: (usize, usize)) -> A {
-        self.__query(0, range, (0, self.n - 1))
+    pub fn try_query(&self, range: impl RangeBounds<usize>) -> Result<M::S, SegmentTreeError> {
+        let (start, end) = __checked_bounds(range, self.n)?;
+        if start == end {
+            return Ok(self.monoid.identity());
+        }
+        Ok(self.__query(0, (start, end - 1), (0, self.n - 1)))
     }
     /// # Arguments
     ///
@@ -107,22 +242,22 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
     /// * `range`: Range of the query
     /// * `cur_range`: Range of the current node in the segment tree
     ///
-    /// returns: `A`
+    /// returns: `M::S`
     fn __query(
         &self,
         tree_index: usize,
         range: (usize, usize),
         cur_range: (usize, usize),
-    ) -> A {
+    ) -> M::S {
         let (l, r) = range;
         let (cl, cr) = cur_range;
         // if cur_range is inside range
         if l <= cl && cr <= r {
             return self.tree_arr[tree_index].clone();
         }
-        // if cur_range and range are disjoint, return the fallback value
+        // if cur_range and range are disjoint, return the identity
         if cr < l || r < cl {
-            return self.fallback_value.clone();
+            return self.monoid.identity();
         }
         // else process the left and right subtree until the subtree range is inside the query range or disjoint
         let cm = (cl + cr) / 2;
@@ -136,31 +271,57 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
             range,
             (cm + 1, cr),
         );
-        return (self.query_operation)(&left_subtree_value, &right_subtree_value);
+        return self.monoid.combine(&left_subtree_value, &right_subtree_value);
     }
     ///
     ///
     /// # Arguments
     ///
     /// * `value`: New Value to be updated
-    /// * `range`: Range on which the update is to be performed
+    /// * `range`: Range on which the update is to be performed, any of `l..r`, `l..=r`, `..r`, `l..` or `..`
     ///
     /// returns: ()
+    ///
+    /// Debug builds panic with a descriptive message if the range is inverted or out of bounds,
+    /// use `try_update` to handle those cases.
     pub fn update(
         &mut self,
-        value: &A,
-        range: (usize, usize),
+        value: &M::S,
+        range: impl RangeBounds<usize>,
     ) {
+        let (start, end) = __bounds(range, self.n).unwrap_or_else(|e| panic!("{}", e));
+        debug_assert!(start <= end, "{}", SegmentTreeError::InvertedRange { start, end });
+        debug_assert!(end <= self.n, "{}", SegmentTreeError::OutOfBounds { end, len: self.n });
+        if start >= end {
+            return;
+        }
         self.__update(
             value,
             0,
-            range,
+            (start, end - 1),
             (0, self.n - 1),
         );
     }
     /// # Arguments
     ///
     /// * `value`: New Value to be updated
+    /// * `range`: Range on which the update is to be performed
+    ///
+    /// returns: Result<(), SegmentTreeError>, nothing is updated on error
+    pub fn try_update(
+        &mut self,
+        value: &M::S,
+        range: impl RangeBounds<usize>,
+    ) -> Result<(), SegmentTreeError> {
+        let (start, end) = __checked_bounds(range, self.n)?;
+        if start < end {
+            self.__update(value, 0, (start, end - 1), (0, self.n - 1));
+        }
+        Ok(())
+    }
+    /// # Arguments
+    ///
+    /// * `value`: New Value to be updated
     /// * `tree_index`: Index of the current node in the segment tree
     /// * `range`: Range on which the update is to be performed
     /// * `cur_range`: Current range of the node in the segment tree
@@ -168,7 +329,7 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
     /// returns: ()
     fn __update(
         &mut self,
-        value: &A,
+        value: &M::S,
         tree_index: usize,
         range: (usize, usize),
         cur_range: (usize, usize),
@@ -202,8 +363,312 @@ impl<A: Default + Clone, QO, NO> SegmentTree<A, QO, NO>
         );
         let left_subtree_value = self.tree_arr[tree_index * 2 + 1].clone();
         let right_subtree_value = self.tree_arr[tree_index * 2 + 2].clone();
-        self.tree_arr[tree_index] = (self.query_operation)(&left_subtree_value, &right_subtree_value);
+        self.tree_arr[tree_index] = self.monoid.combine(&left_subtree_value, &right_subtree_value);
+    }
+    /// Descends the tree from `l` to the right while the aggregate satisfies `pred`
+    ///
+    /// # Arguments
+    ///
+    /// * `l`: Start of the range, `l <= n`
+    /// * `pred`: Monotone predicate on the aggregate, `pred(identity)` must be true
+    ///
+    /// returns: usize
+    ///
+    /// the largest `r` in `[l, n]` such that `pred(query(l..r))` holds,
+    /// i.e. `r` is the first index where the aggregate of `l..=r` breaks `pred` (or `n` if it never does)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let segment_tree: SegmentTree<Sum<i64>> = SegmentTree::from_monoid(&vec![1, 2, 3, 4], Sum::new());
+    /// let r = segment_tree.max_right(0, |sum| *sum <= 5); // 2, the prefix 1 + 2 + 3 is the first to exceed 5
+    /// ```
+    pub fn max_right<P>(&self, l: usize, pred: P) -> usize
+        where
+            P: Fn(&M::S) -> bool {
+        debug_assert!(l <= self.n, "{}", SegmentTreeError::OutOfBounds { end: l, len: self.n });
+        debug_assert!(pred(&self.monoid.identity()));
+        if l == self.n {
+            return self.n;
+        }
+        let mut acc = self.monoid.identity();
+        self.__max_right(0, l, (0, self.n - 1), &pred, &mut acc).unwrap_or(self.n)
+    }
+    /// # Arguments
+    ///
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `l`: Start of the range
+    /// * `cur_range`: Range of the current node in the segment tree
+    /// * `pred`: Predicate on the aggregate
+    /// * `acc`: Aggregate of the elements from `l` to the start of `cur_range`
+    ///
+    /// returns: Option<usize>, the index where `pred` breaks inside `cur_range` if there is one
+    fn __max_right<P>(
+        &self,
+        tree_index: usize,
+        l: usize,
+        cur_range: (usize, usize),
+        pred: &P,
+        acc: &mut M::S,
+    ) -> Option<usize>
+        where
+            P: Fn(&M::S) -> bool {
+        let (cl, cr) = cur_range;
+        // if cur_range is to the left of l
+        if cr < l {
+            return None;
+        }
+        // if cur_range is inside [l, n) the whole node can be taken when it keeps pred true
+        if l <= cl {
+            let merged = self.monoid.combine(acc, &self.tree_arr[tree_index]);
+            if pred(&merged) {
+                *acc = merged;
+                return None;
+            }
+            if cl == cr {
+                return Some(cl);
+            }
+        }
+        // else the answer lies in the left subtree or, failing that, the right subtree
+        let cm = (cl + cr) / 2;
+        self.__max_right(tree_index * 2 + 1, l, (cl, cm), pred, acc)
+            .or_else(|| self.__max_right(tree_index * 2 + 2, l, (cm + 1, cr), pred, acc))
+    }
+    /// Descends the tree from `r` to the left while the aggregate satisfies `pred`
+    ///
+    /// # Arguments
+    ///
+    /// * `r`: End of the range (exclusive), `r <= n`
+    /// * `pred`: Monotone predicate on the aggregate, `pred(identity)` must be true
+    ///
+    /// returns: usize
+    ///
+    /// the smallest `l` in `[0, r]` such that `pred(query(l..r))` holds,
+    /// i.e. `l - 1` is the last index where the aggregate of `l - 1..r` breaks `pred` (or `0` if it never does)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let segment_tree: SegmentTree<Sum<i64>> = SegmentTree::from_monoid(&vec![1, 2, 3, 4], Sum::new());
+    /// let l = segment_tree.min_left(4, |sum| *sum <= 7); // 2, the suffix 2 + 3 + 4 is the first to exceed 7
+    /// ```
+    pub fn min_left<P>(&self, r: usize, pred: P) -> usize
+        where
+            P: Fn(&M::S) -> bool {
+        debug_assert!(r <= self.n, "{}", SegmentTreeError::OutOfBounds { end: r, len: self.n });
+        debug_assert!(pred(&self.monoid.identity()));
+        if r == 0 {
+            return 0;
+        }
+        let mut acc = self.monoid.identity();
+        self.__min_left(0, r, (0, self.n - 1), &pred, &mut acc).map_or(0, |i| i + 1)
+    }
+    /// # Arguments
+    ///
+    /// * `tree_index`: Index of the current node in the segment tree
+    /// * `r`: End of the range (exclusive)
+    /// * `cur_range`: Range of the current node in the segment tree
+    /// * `pred`: Predicate on the aggregate
+    /// * `acc`: Aggregate of the elements from the end of `cur_range` to `r`
+    ///
+    /// returns: Option<usize>, the index where `pred` breaks inside `cur_range` if there is one
+    fn __min_left<P>(
+        &self,
+        tree_index: usize,
+        r: usize,
+        cur_range: (usize, usize),
+        pred: &P,
+        acc: &mut M::S,
+    ) -> Option<usize>
+        where
+            P: Fn(&M::S) -> bool {
+        let (cl, cr) = cur_range;
+        // if cur_range is to the right of r
+        if r <= cl {
+            return None;
+        }
+        // if cur_range is inside [0, r) the whole node can be taken when it keeps pred true
+        if cr < r {
+            let merged = self.monoid.combine(&self.tree_arr[tree_index], acc);
+            if pred(&merged) {
+                *acc = merged;
+                return None;
+            }
+            if cl == cr {
+                return Some(cl);
+            }
+        }
+        // else the answer lies in the right subtree or, failing that, the left subtree
+        let cm = (cl + cr) / 2;
+        self.__min_left(tree_index * 2 + 2, r, (cm + 1, cr), pred, acc)
+            .or_else(|| self.__min_left(tree_index * 2 + 1, r, (cl, cm), pred, acc))
     }
 }
 
 
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::monoid::{Gcd, Max, Sum};
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_closure_constructor() {
+        let inp_arr: Vec<i64> = vec![0; 4];
+        let mut segment_tree = SegmentTree::new(
+            &inp_arr,
+            |a: &i64, b: &i64| *a.max(b),
+            |a: &i64, b: &i64| a + b,
+            i64::MIN,
+        );
+        segment_tree.update(&3, 0..=0);
+        segment_tree.update(&1, 1..=3);
+        assert_eq!(segment_tree.query(0..=3), 3);
+        assert_eq!(segment_tree.query(1..=3), 1);
+    }
+
+    #[test]
+    fn test_monoid_constructor() {
+        let mut segment_tree = SegmentTree::from_monoid(&vec![3, 1, 4, 1, 5], Sum::<i64>::new());
+        assert_eq!(segment_tree.query(0..=4), 14);
+        segment_tree.update(&10, 1..=1);
+        assert_eq!(segment_tree.query(0..=4), 23);
+        assert_eq!(segment_tree.query(2..=3), 5);
+
+        let mut segment_tree = SegmentTree::with_monoid(&vec![4u64, 6, 8], Gcd::<u64>::new(), |a: &u64, b: &u64| a * b);
+        assert_eq!(segment_tree.query(0..=2), 2);
+        segment_tree.update(&3, 0..=2);
+        assert_eq!(segment_tree.query(0..=2), 6);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let mut segment_tree = SegmentTree::from_monoid(&vec![1, 2, 3, 4, 5], Sum::<i64>::new());
+        assert_eq!(segment_tree.query(..), 15);
+        assert_eq!(segment_tree.query(1..3), 5);
+        assert_eq!(segment_tree.query(..=1), 3);
+        assert_eq!(segment_tree.query(3..), 9);
+        assert_eq!(segment_tree.query(2..2), 0);
+        segment_tree.update(&0, ..2);
+        assert_eq!(segment_tree.query(..), 12);
+    }
+
+    #[test]
+    fn test_checked_api() {
+        let mut segment_tree = SegmentTree::from_monoid(&vec![1, 2, 3], Sum::<i64>::new());
+        assert_eq!(segment_tree.try_query(0..3), Ok(6));
+        assert_eq!(segment_tree.try_query(3..3), Ok(0));
+        let (start, end) = (2, 1);
+        assert_eq!(segment_tree.try_query(start..end), Err(SegmentTreeError::InvertedRange { start: 2, end: 1 }));
+        assert_eq!(segment_tree.try_query(0..=3), Err(SegmentTreeError::OutOfBounds { end: 4, len: 3 }));
+        assert_eq!(segment_tree.try_update(&7, 1..=5), Err(SegmentTreeError::OutOfBounds { end: 6, len: 3 }));
+        // the inclusive end would overflow usize
+        assert_eq!(segment_tree.try_query(0..=usize::MAX), Err(SegmentTreeError::OutOfBounds { end: usize::MAX, len: 3 }));
+        assert_eq!(segment_tree.try_update(&7, (Bound::Excluded(usize::MAX), Bound::Unbounded)), Err(SegmentTreeError::OutOfBounds { end: usize::MAX, len: 3 }));
+        assert_eq!(segment_tree.query(..), 6);
+        assert_eq!(segment_tree.try_update(&7, 1..=1), Ok(()));
+        assert_eq!(segment_tree.query(..), 11);
+        assert_eq!(
+            SegmentTreeError::OutOfBounds { end: 4, len: 3 }.to_string(),
+            "segment tree range end 4 is out of bounds for length 3"
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let mut segment_tree = SegmentTree::from_monoid(&vec![], Max::<i64>::new());
+        assert!(segment_tree.is_empty());
+        assert_eq!(segment_tree.query(..), i64::MIN);
+        segment_tree.update(&3, ..);
+        assert_eq!(segment_tree.try_query(0..1), Err(SegmentTreeError::OutOfBounds { end: 1, len: 0 }));
+        assert_eq!(segment_tree.max_right(0, |max| *max < 0), 0);
+        assert_eq!(segment_tree.min_left(0, |max| *max < 0), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "segment tree range end 5 is out of bounds for length 3")]
+    fn test_out_of_bounds_query_panics() {
+        let segment_tree = SegmentTree::from_monoid(&vec![1, 2, 3], Sum::<i64>::new());
+        segment_tree.query(2..=4);
+    }
+
+    fn point_assign_range_max_prop(a: Vec<i64>, ops: Vec<(usize, usize, i64)>) {
+        let n = a.len();
+        let mut brute = a.clone();
+        let mut segment_tree = SegmentTree::from_monoid(&a, Max::<i64>::new());
+        for (x, y, v) in ops {
+            if v % 2 == 0 {
+                let i = x % n;
+                segment_tree.update(&v, i..=i);
+                brute[i] = v;
+            } else {
+                let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+                assert_eq!(segment_tree.query(l..=r), *brute[l..=r].iter().max().unwrap());
+            }
+        }
+    }
+    #[test]
+    fn test_max_right_min_left() {
+        let segment_tree: SegmentTree<Sum<i64>> = SegmentTree::from_monoid(&vec![1, 2, 3, 4], Sum::new());
+        assert_eq!(segment_tree.max_right(0, |sum| *sum <= 5), 2);
+        assert_eq!(segment_tree.max_right(0, |sum| *sum <= 10), 4);
+        assert_eq!(segment_tree.max_right(1, |sum| *sum <= 1), 1);
+        assert_eq!(segment_tree.max_right(4, |sum| *sum <= 0), 4);
+        assert_eq!(segment_tree.min_left(4, |sum| *sum <= 7), 2);
+        assert_eq!(segment_tree.min_left(4, |sum| *sum <= 10), 0);
+        assert_eq!(segment_tree.min_left(2, |sum| *sum <= 1), 2);
+        assert_eq!(segment_tree.min_left(0, |sum| *sum <= 0), 0);
+
+        // first element >= x
+        let segment_tree = SegmentTree::from_monoid(&vec![3, 1, 4, 1, 5, 9, 2, 6], Max::<i64>::new());
+        assert_eq!(segment_tree.max_right(0, |max| *max < 5), 4);
+        assert_eq!(segment_tree.max_right(5, |max| *max < 5), 5);
+        assert_eq!(segment_tree.max_right(6, |max| *max < 10), 8);
+    }
+
+    fn max_right_min_left_prop(a: Vec<i64>, k: i64, i: usize) {
+        let n = a.len();
+        let i = i % (n + 1);
+        let segment_tree = SegmentTree::from_monoid(&a, Sum::<i64>::new());
+        let pred = |sum: &i64| *sum <= k;
+
+        let mut expected_r = i;
+        while expected_r < n && a[i..=expected_r].iter().sum::<i64>() <= k {
+            expected_r += 1;
+        }
+        assert_eq!(segment_tree.max_right(i, pred), expected_r);
+
+        let mut expected_l = i;
+        while expected_l > 0 && a[expected_l - 1..i].iter().sum::<i64>() <= k {
+            expected_l -= 1;
+        }
+        assert_eq!(segment_tree.min_left(i, pred), expected_l);
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn point_assign_range_max(
+            a in vec(-1000i64..=1000, 1..=100).boxed(),
+            ops in vec((0usize..100, 0usize..100, -1000i64..=1000), 1..=100).boxed(),
+        ) {
+            point_assign_range_max_prop(a, ops);
+        }
+        #[test]
+        fn max_right_min_left(
+            a in vec(0i64..=100, 1..=100).boxed(),
+            k in 0i64..=2000,
+            i in 0usize..=100,
+        ) {
+            max_right_min_left_prop(a, k, i);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+#![allow(dead_code)]
+use crate::data_structures::monoid::{FnMonoid, Monoid};
+
+/*
+    2D Segment Tree
+    --------------------------------------------------------------
+    Segment tree over the rows whose every node is a segment tree
+    over the columns, both in the bottom-up `2 * n` layout of
+    IterativeSegmentTree. The monoid has to be commutative.
+    Build: O(n * m), Query: O(log n * log m), Update: O(log n * log m)
+    Memory: 4 * n * m values
+    --------------------------------------------------------------
+ */
+pub struct SegmentTree2D<M, NO = fn(&<M as Monoid>::S, &<M as Monoid>::S) -> <M as Monoid>::S>
+    where
+        M: Monoid,
+        NO: Fn(&M::S, &M::S) -> M::S {
+    n: usize,
+    m: usize,
+    tree_arr: Vec<Vec<M::S>>,
+    monoid: M,
+    node_operation: NO,
+}
+
+impl<A: Clone, QO, NO> SegmentTree2D<FnMonoid<A, QO>, NO>
+    where
+        QO: Fn(&A, &A) -> A,
+        NO: Fn(&A, &A) -> A {
+    /// # Arguments
+    ///
+    /// * `grid`: `n x m` grid on which the segment tree is built
+    /// * `query_operation`: It is the (commutative) operation that is performed on two subtree values to get the current node value
+    /// * `node_operation`: It is the operation that is performed on the current cell value and the value to be updated
+    /// * `fallback_value`: It is the value that is returned when the query range and the current range are disjoint
+    ///
+    /// returns: SegmentTree2D<FnMonoid<A, QO>, NO>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let grid: Vec<Vec<i64>> = vec![vec![0; 3]; 2];
+    /// let mut segment_tree = SegmentTree2D::new(
+    ///    &grid,
+    ///   |a: &i64, b: &i64| a + b,
+    ///   |a: &i64, b: &i64| a + b,
+    ///   0,
+    /// );
+    /// segment_tree.update(&1, (1, 2)); // add 1 to grid[1][2]
+    /// let count = segment_tree.query((0, 0), (1, 2)); // sum of the whole grid
+    /// ```
+    pub fn new(
+        grid: &Vec<Vec<A>>,
+        query_operation: QO,
+        node_operation: NO,
+        fallback_value: A,
+    ) -> Self {
+        SegmentTree2D::with_monoid(grid, FnMonoid::new(query_operation, fallback_value), node_operation)
+    }
+}
+
+impl<M: Monoid> SegmentTree2D<M> {
+    /// Builds a 2D segment tree whose `update` assigns the new value to the cell
+    pub fn from_monoid(grid: &Vec<Vec<M::S>>, monoid: M) -> Self {
+        SegmentTree2D::with_monoid(grid, monoid, |_: &M::S, b: &M::S| b.clone())
+    }
+}
+
+impl<M: Monoid, NO> SegmentTree2D<M, NO>
+    where
+        NO: Fn(&M::S, &M::S) -> M::S {
+    /// # Arguments
+    ///
+    /// * `grid`: `n x m` grid on which the segment tree is built, every row must have the same length
+    /// * `monoid`: Commutative monoid used to combine subtree values, its identity is returned for empty ranges
+    /// * `node_operation`: It is the operation that is performed on the current cell value and the value to be updated
+    ///
+    /// returns: SegmentTree2D<M, NO>
+    pub fn with_monoid(
+        grid: &Vec<Vec<M::S>>,
+        monoid: M,
+        node_operation: NO,
+    ) -> Self {
+        let n = grid.len();
+        let m = if n == 0 { 0 } else { grid[0].len() };
+        let mut tree_arr = vec![vec![monoid.identity(); 2 * m]; 2 * n];
+        for (i, row) in grid.iter().enumerate() {
+            tree_arr[n + i][m..].clone_from_slice(row);
+            for j in (1..m).rev() {
+                tree_arr[n + i][j] = monoid.combine(&tree_arr[n + i][2 * j], &tree_arr[n + i][2 * j + 1]);
+            }
+        }
+        for i in (1..n).rev() {
+            for j in 1..2 * m {
+                tree_arr[i][j] = monoid.combine(&tree_arr[2 * i][j], &tree_arr[2 * i + 1][j]);
+            }
+        }
+        SegmentTree2D {
+            n,
+            m,
+            tree_arr,
+            monoid,
+            node_operation,
+        }
+    }
+    /// returns: the aggregate of the columns `y1..=y2` of the row node `i`
+    fn __query_row(&self, i: usize, y1: usize, y2: usize) -> M::S {
+        let row = &self.tree_arr[i];
+        let mut value = self.monoid.identity();
+        let mut l = y1 + self.m;
+        let mut r = y2 + self.m + 1;
+        while l < r {
+            if l & 1 == 1 {
+                value = self.monoid.combine(&value, &row[l]);
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                value = self.monoid.combine(&value, &row[r]);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        value
+    }
+    /// # Arguments
+    ///
+    /// * `top_left`: `(x1, y1)`, first row and column of the rectangle
+    /// * `bottom_right`: `(x2, y2)`, last row and column of the rectangle (inclusive)
+    ///
+    /// returns: M::S
+    ///
+    /// # Examples
+    ///
+    /// ```
+    ///  segment_tree.query((0, 0), (n - 1, m - 1)); // query the whole grid using the query_operation
+    /// ```
+    pub fn query(&self, top_left: (usize, usize), bottom_right: (usize, usize)) -> M::S {
+        let (x1, y1) = top_left;
+        let (x2, y2) = bottom_right;
+        let mut value = self.monoid.identity();
+        let mut l = x1 + self.n;
+        let mut r = x2 + self.n + 1;
+        while l < r {
+            if l & 1 == 1 {
+                value = self.monoid.combine(&value, &self.__query_row(l, y1, y2));
+                l += 1;
+            }
+            if r & 1 == 1 {
+                r -= 1;
+                value = self.monoid.combine(&value, &self.__query_row(r, y1, y2));
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        value
+    }
+    /// # Arguments
+    ///
+    /// * `value`: New Value to be updated
+    /// * `cell`: `(x, y)`, row and column of the cell
+    ///
+    /// returns: ()
+    pub fn update(&mut self, value: &M::S, cell: (usize, usize)) {
+        let (x, y) = cell;
+        let mut i = x + self.n;
+        let leaf = y + self.m;
+        self.tree_arr[i][leaf] = (self.node_operation)(&self.tree_arr[i][leaf], value);
+        let mut j = leaf >> 1;
+        while j > 0 {
+            self.tree_arr[i][j] = self.monoid.combine(&self.tree_arr[i][2 * j], &self.tree_arr[i][2 * j + 1]);
+            j >>= 1;
+        }
+        // every row node above the leaf row only changes along the column path of `leaf`
+        i >>= 1;
+        while i > 0 {
+            let mut j = leaf;
+            while j > 0 {
+                self.tree_arr[i][j] = self.monoid.combine(&self.tree_arr[2 * i][j], &self.tree_arr[2 * i + 1][j]);
+                j >>= 1;
+            }
+            i >>= 1;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::monoid::Max;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_count_points_in_rectangle() {
+        let grid: Vec<Vec<i64>> = vec![vec![0; 3]; 2];
+        let mut segment_tree = SegmentTree2D::new(&grid, |a: &i64, b: &i64| a + b, |a: &i64, b: &i64| a + b, 0);
+        segment_tree.update(&1, (1, 2));
+        segment_tree.update(&1, (0, 0));
+        segment_tree.update(&1, (1, 0));
+        assert_eq!(segment_tree.query((0, 0), (1, 2)), 3);
+        assert_eq!(segment_tree.query((0, 0), (0, 2)), 1);
+        assert_eq!(segment_tree.query((0, 1), (1, 2)), 1);
+        assert_eq!(segment_tree.query((1, 0), (1, 0)), 1);
+    }
+
+    fn rectangle_max_prop(grid: Vec<Vec<i64>>, ops: Vec<(usize, usize, usize, usize, i64)>) {
+        let (n, m) = (grid.len(), grid[0].len());
+        let mut brute = grid.clone();
+        let mut segment_tree = SegmentTree2D::from_monoid(&grid, Max::<i64>::new());
+        for (x1, y1, x2, y2, v) in ops {
+            if v % 2 == 0 {
+                segment_tree.update(&v, (x1 % n, y1 % m));
+                brute[x1 % n][y1 % m] = v;
+            }
+            let (xl, xr) = ((x1 % n).min(x2 % n), (x1 % n).max(x2 % n));
+            let (yl, yr) = ((y1 % m).min(y2 % m), (y1 % m).max(y2 % m));
+            let expected = brute[xl..=xr].iter().flat_map(|row| row[yl..=yr].iter()).max().unwrap();
+            assert_eq!(segment_tree.query((xl, yl), (xr, yr)), *expected);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn rectangle_max(
+            (grid, ops) in (1usize..=10, 1usize..=10).prop_flat_map(|(n, m)| (
+                vec(vec(-1000i64..=1000, m), n),
+                vec((0usize..100, 0usize..100, 0usize..100, 0usize..100, -1000i64..=1000), 1..=100),
+            )),
+        ) {
+            rectangle_max_prop(grid, ops);
+        }
+    }
+}
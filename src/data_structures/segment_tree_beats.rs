@@ -0,0 +1,351 @@
+#![allow(dead_code)]
+
+/*
+    Segment Tree Beats (Ji Driver Segment Tree)
+    --------------------------------------------------------------
+    Supports range chmin (a[i] = min(a[i], x)), range chmax
+    (a[i] = max(a[i], x)) and range add together with range sum,
+    range min and range max queries.
+    Every node keeps its largest and second largest value (and the
+    count of the largest), a chmin with second_max < x < max only
+    touches the largest values so it can be applied to the node as
+    a tag. Same for chmax with the smallest values on the minimum side.
+    Time: amortized O(log^2 n) per operation, O(log n) without add
+    --------------------------------------------------------------
+ */
+#[derive(Clone, Default)]
+struct BeatsNode {
+    sum: i64,
+    max1: i64,
+    max2: i64,
+    max_cnt: i64,
+    min1: i64,
+    min2: i64,
+    min_cnt: i64,
+    lazy_add: i64,
+}
+
+pub struct SegmentTreeBeats {
+    n: usize,
+    tree_arr: Vec<BeatsNode>,
+}
+
+impl SegmentTreeBeats {
+    /// # Arguments
+    ///
+    /// * `inp_arr`: Array on which the segment tree is built
+    ///
+    /// returns: SegmentTreeBeats
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut segment_tree = SegmentTreeBeats::new(&vec![5, 1, 4, 2, 3]);
+    /// segment_tree.chmin((0, 4), 3); // [3, 1, 3, 2, 3]
+    /// segment_tree.add((1, 3), 2); // [3, 3, 5, 4, 3]
+    /// let sum = segment_tree.query_sum((0, 4)); // 18
+    /// ```
+    pub fn new(inp_arr: &Vec<i64>) -> Self {
+        let n = inp_arr.len();
+        let mut a = SegmentTreeBeats {
+            n,
+            tree_arr: vec![BeatsNode::default(); 4 * n],
+        };
+        a.__build(inp_arr, 0, (0, n - 1));
+        return a;
+    }
+    fn __build(
+        &mut self,
+        inp_arr: &Vec<i64>,
+        tree_index: usize,
+        cur_range: (usize, usize),
+    ) {
+        let (cl, cr) = cur_range;
+        // if it is a leaf node
+        if cl == cr {
+            self.tree_arr[tree_index] = BeatsNode {
+                sum: inp_arr[cl],
+                max1: inp_arr[cl],
+                max2: i64::MIN,
+                max_cnt: 1,
+                min1: inp_arr[cl],
+                min2: i64::MAX,
+                min_cnt: 1,
+                lazy_add: 0,
+            };
+            return;
+        }
+        let cm = (cl + cr) / 2;
+        self.__build(inp_arr, tree_index * 2 + 1, (cl, cm));
+        self.__build(inp_arr, tree_index * 2 + 2, (cm + 1, cr));
+        self.__pull(tree_index);
+    }
+    /// Recomputes the value of `tree_index` from its two children
+    fn __pull(&mut self, tree_index: usize) {
+        let left = &self.tree_arr[tree_index * 2 + 1];
+        let right = &self.tree_arr[tree_index * 2 + 2];
+        let sum = left.sum + right.sum;
+        let (max1, max2, max_cnt) = if left.max1 == right.max1 {
+            (left.max1, left.max2.max(right.max2), left.max_cnt + right.max_cnt)
+        } else if left.max1 > right.max1 {
+            (left.max1, left.max2.max(right.max1), left.max_cnt)
+        } else {
+            (right.max1, left.max1.max(right.max2), right.max_cnt)
+        };
+        let (min1, min2, min_cnt) = if left.min1 == right.min1 {
+            (left.min1, left.min2.min(right.min2), left.min_cnt + right.min_cnt)
+        } else if left.min1 < right.min1 {
+            (left.min1, left.min2.min(right.min1), left.min_cnt)
+        } else {
+            (right.min1, left.min1.min(right.min2), right.min_cnt)
+        };
+        self.tree_arr[tree_index] = BeatsNode { sum, max1, max2, max_cnt, min1, min2, min_cnt, lazy_add: 0 };
+    }
+    /// Adds `value` to every element of the node covering `len` elements
+    fn __apply_add(&mut self, tree_index: usize, value: i64, len: usize) {
+        let node = &mut self.tree_arr[tree_index];
+        node.sum += value * len as i64;
+        node.max1 += value;
+        if node.max2 != i64::MIN {
+            node.max2 += value;
+        }
+        node.min1 += value;
+        if node.min2 != i64::MAX {
+            node.min2 += value;
+        }
+        node.lazy_add += value;
+    }
+    /// Lowers the largest values of the node to `value`, requires `max2 < value < max1`
+    fn __apply_chmin(&mut self, tree_index: usize, value: i64) {
+        let node = &mut self.tree_arr[tree_index];
+        node.sum -= (node.max1 - value) * node.max_cnt;
+        if node.min1 == node.max1 {
+            node.min1 = value;
+        } else if node.min2 == node.max1 {
+            node.min2 = value;
+        }
+        node.max1 = value;
+    }
+    /// Raises the smallest values of the node to `value`, requires `min1 < value < min2`
+    fn __apply_chmax(&mut self, tree_index: usize, value: i64) {
+        let node = &mut self.tree_arr[tree_index];
+        node.sum += (value - node.min1) * node.min_cnt;
+        if node.max1 == node.min1 {
+            node.max1 = value;
+        } else if node.max2 == node.min1 {
+            node.max2 = value;
+        }
+        node.min1 = value;
+    }
+    /// Moves the pending add and the clamps implied by the node's max/min down to its two children
+    fn __push(&mut self, tree_index: usize, cur_range: (usize, usize)) {
+        let (cl, cr) = cur_range;
+        let cm = (cl + cr) / 2;
+        let (left, right) = (tree_index * 2 + 1, tree_index * 2 + 2);
+        let lazy_add = self.tree_arr[tree_index].lazy_add;
+        if lazy_add != 0 {
+            self.__apply_add(left, lazy_add, cm - cl + 1);
+            self.__apply_add(right, lazy_add, cr - cm);
+            self.tree_arr[tree_index].lazy_add = 0;
+        }
+        let (max1, min1) = (self.tree_arr[tree_index].max1, self.tree_arr[tree_index].min1);
+        for child in [left, right] {
+            if self.tree_arr[child].max1 > max1 {
+                self.__apply_chmin(child, max1);
+            }
+            if self.tree_arr[child].min1 < min1 {
+                self.__apply_chmax(child, min1);
+            }
+        }
+    }
+    /// Sets `a[i] = min(a[i], value)` for every `i` in the range
+    pub fn chmin(&mut self, range: (usize, usize), value: i64) {
+        self.__chmin(0, range, (0, self.n - 1), value);
+    }
+    fn __chmin(
+        &mut self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+        value: i64,
+    ) {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint or nothing is above value
+        if cr < l || r < cl || self.tree_arr[tree_index].max1 <= value {
+            return;
+        }
+        // if only the largest values change, tag the node and stop here
+        if l <= cl && cr <= r && self.tree_arr[tree_index].max2 < value {
+            self.__apply_chmin(tree_index, value);
+            return;
+        }
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        self.__chmin(tree_index * 2 + 1, range, (cl, cm), value);
+        self.__chmin(tree_index * 2 + 2, range, (cm + 1, cr), value);
+        self.__pull(tree_index);
+    }
+    /// Sets `a[i] = max(a[i], value)` for every `i` in the range
+    pub fn chmax(&mut self, range: (usize, usize), value: i64) {
+        self.__chmax(0, range, (0, self.n - 1), value);
+    }
+    fn __chmax(
+        &mut self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+        value: i64,
+    ) {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint or nothing is below value
+        if cr < l || r < cl || self.tree_arr[tree_index].min1 >= value {
+            return;
+        }
+        // if only the smallest values change, tag the node and stop here
+        if l <= cl && cr <= r && self.tree_arr[tree_index].min2 > value {
+            self.__apply_chmax(tree_index, value);
+            return;
+        }
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        self.__chmax(tree_index * 2 + 1, range, (cl, cm), value);
+        self.__chmax(tree_index * 2 + 2, range, (cm + 1, cr), value);
+        self.__pull(tree_index);
+    }
+    /// Adds `value` to every element in the range
+    pub fn add(&mut self, range: (usize, usize), value: i64) {
+        self.__add(0, range, (0, self.n - 1), value);
+    }
+    fn __add(
+        &mut self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+        value: i64,
+    ) {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range and range are disjoint
+        if cr < l || r < cl {
+            return;
+        }
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            self.__apply_add(tree_index, value, cr - cl + 1);
+            return;
+        }
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        self.__add(tree_index * 2 + 1, range, (cl, cm), value);
+        self.__add(tree_index * 2 + 2, range, (cm + 1, cr), value);
+        self.__pull(tree_index);
+    }
+    /// Folds `get` over the nodes that exactly cover the range, starting from `fallback_value`
+    fn __query<G, F>(
+        &mut self,
+        tree_index: usize,
+        range: (usize, usize),
+        cur_range: (usize, usize),
+        get: &G,
+        fold: &F,
+        fallback_value: i64,
+    ) -> i64
+        where
+            G: Fn(&BeatsNode) -> i64,
+            F: Fn(i64, i64) -> i64 {
+        let (l, r) = range;
+        let (cl, cr) = cur_range;
+        // if cur_range is inside range
+        if l <= cl && cr <= r {
+            return get(&self.tree_arr[tree_index]);
+        }
+        // if cur_range and range are disjoint, return the fallback value
+        if cr < l || r < cl {
+            return fallback_value;
+        }
+        self.__push(tree_index, cur_range);
+        let cm = (cl + cr) / 2;
+        let left_subtree_value = self.__query(tree_index * 2 + 1, range, (cl, cm), get, fold, fallback_value);
+        let right_subtree_value = self.__query(tree_index * 2 + 2, range, (cm + 1, cr), get, fold, fallback_value);
+        return fold(left_subtree_value, right_subtree_value);
+    }
+    /// returns: the sum of the elements in the range
+    pub fn query_sum(&mut self, range: (usize, usize)) -> i64 {
+        self.__query(0, range, (0, self.n - 1), &|node: &BeatsNode| node.sum, &|a, b| a + b, 0)
+    }
+    /// returns: the largest element in the range
+    pub fn query_max(&mut self, range: (usize, usize)) -> i64 {
+        self.__query(0, range, (0, self.n - 1), &|node: &BeatsNode| node.max1, &|a, b| a.max(b), i64::MIN)
+    }
+    /// returns: the smallest element in the range
+    pub fn query_min(&mut self, range: (usize, usize)) -> i64 {
+        self.__query(0, range, (0, self.n - 1), &|node: &BeatsNode| node.min1, &|a, b| a.min(b), i64::MAX)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    #[test]
+    fn test_segment_tree_beats() {
+        let mut segment_tree = SegmentTreeBeats::new(&vec![5, 1, 4, 2, 3]);
+        segment_tree.chmin((0, 4), 3);
+        assert_eq!(segment_tree.query_sum((0, 4)), 12);
+        segment_tree.add((1, 3), 2);
+        assert_eq!(segment_tree.query_sum((0, 4)), 18);
+        segment_tree.chmax((0, 2), 4);
+        assert_eq!(segment_tree.query_sum((0, 4)), 20);
+        assert_eq!(segment_tree.query_max((0, 4)), 5);
+        assert_eq!(segment_tree.query_min((0, 4)), 3);
+        assert_eq!(segment_tree.query_min((0, 2)), 4);
+    }
+
+    fn beats_prop(a: Vec<i64>, ops: Vec<(u8, usize, usize, i64)>) {
+        let n = a.len();
+        let mut brute = a.clone();
+        let mut segment_tree = SegmentTreeBeats::new(&a);
+        for (op, x, y, v) in ops {
+            let (l, r) = ((x % n).min(y % n), (x % n).max(y % n));
+            match op % 4 {
+                0 => {
+                    segment_tree.chmin((l, r), v);
+                    brute[l..=r].iter_mut().for_each(|e| *e = (*e).min(v));
+                }
+                1 => {
+                    segment_tree.chmax((l, r), v);
+                    brute[l..=r].iter_mut().for_each(|e| *e = (*e).max(v));
+                }
+                2 => {
+                    segment_tree.add((l, r), v);
+                    brute[l..=r].iter_mut().for_each(|e| *e += v);
+                }
+                _ => {
+                    assert_eq!(segment_tree.query_sum((l, r)), brute[l..=r].iter().sum::<i64>());
+                    assert_eq!(segment_tree.query_max((l, r)), *brute[l..=r].iter().max().unwrap());
+                    assert_eq!(segment_tree.query_min((l, r)), *brute[l..=r].iter().min().unwrap());
+                }
+            }
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn beats(
+            a in vec(-100i64..=100, 1..=100).boxed(),
+            ops in vec((0u8..4, 0usize..100, 0usize..100, -100i64..=100), 1..=200).boxed(),
+        ) {
+            beats_prop(a, ops);
+        }
+    }
+}
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 #[derive(Default, Debug)]
 struct TrieNode {
-    word: String,
+    // number of inserted words that go through this node
+    pass_count: usize,
+    // number of inserted words that end at this node
+    end_count: usize,
     children: HashMap<char, TrieNode>,
 }
 
@@ -18,25 +21,164 @@ impl Trie {
         }
     }
 
+    /// Inserts `word`, inserting the same word twice counts it twice
     pub fn insert(&mut self, word: &str) {
         let mut current_node = &mut self.root;
+        current_node.pass_count += 1;
 
         for c in word.chars() {
             current_node = current_node.children.entry(c).or_default();
+            current_node.pass_count += 1;
         }
-        current_node.word = word.to_string();
+        current_node.end_count += 1;
     }
 
-    pub fn contains(&self, word: &str) -> bool {
+    fn find(&self, prefix: &str) -> Option<&TrieNode> {
         let mut current_node = &self.root;
 
-        for c in word.chars() {
+        for c in prefix.chars() {
             match current_node.children.get(&c) {
                 Some(node) => current_node = node,
-                None => return false,
+                None => return None,
             }
         }
 
-        !current_node.word.is_empty()
+        Some(current_node)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.count_words(word) > 0
+    }
+
+    /// returns: true if some inserted word starts with `prefix`
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.count_prefix(prefix) > 0
+    }
+
+    /// returns: how many times `word` is currently in the trie
+    pub fn count_words(&self, word: &str) -> usize {
+        self.find(word).map_or(0, |node| node.end_count)
+    }
+
+    /// returns: how many words currently in the trie start with `prefix` (counted with multiplicity)
+    pub fn count_prefix(&self, prefix: &str) -> usize {
+        self.find(prefix).map_or(0, |node| node.pass_count)
     }
-}
\ No newline at end of file
+
+    /// returns: the total number of words in the trie (counted with multiplicity)
+    pub fn len(&self) -> usize {
+        self.root.pass_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes one occurrence of `word`, the nodes no other word goes through are dropped
+    ///
+    /// returns: false if `word` was not in the trie
+    pub fn remove(&mut self, word: &str) -> bool {
+        if !self.contains(word) {
+            return false;
+        }
+        let mut current_node = &mut self.root;
+        current_node.pass_count -= 1;
+
+        for c in word.chars() {
+            if current_node.children[&c].pass_count == 1 {
+                current_node.children.remove(&c);
+                return true;
+            }
+            current_node = current_node.children.get_mut(&c).unwrap();
+            current_node.pass_count -= 1;
+        }
+        current_node.end_count -= 1;
+        true
+    }
+
+    /// returns: every distinct word that starts with `prefix`, in lexicographic order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut trie = Trie::new();
+    /// for word in ["car", "cat", "cart", "dog"] {
+    ///     trie.insert(word);
+    /// }
+    /// assert_eq!(trie.words_with_prefix("ca"), vec!["car", "cart", "cat"]);
+    /// ```
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            let mut word = prefix.to_string();
+            Self::collect(node, &mut word, &mut words);
+        }
+        words
+    }
+
+    fn collect(node: &TrieNode, word: &mut String, words: &mut Vec<String>) {
+        if node.end_count > 0 {
+            words.push(word.clone());
+        }
+        let mut keys: Vec<&char> = node.children.keys().collect();
+        keys.sort();
+        for &c in keys {
+            word.push(c);
+            Self::collect(&node.children[&c], word, words);
+            word.pop();
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        let mut trie = Trie::new();
+        trie.insert("apple");
+        trie.insert("app");
+        trie.insert("apple");
+        assert!(trie.contains("apple"));
+        assert!(!trie.contains("appl"));
+        assert!(trie.starts_with("appl"));
+        assert!(!trie.starts_with("b"));
+        assert_eq!(trie.count_words("apple"), 2);
+        assert_eq!(trie.count_prefix("app"), 3);
+        assert_eq!(trie.count_prefix("appl"), 2);
+        assert_eq!(trie.count_prefix(""), 3);
+        assert_eq!(trie.len(), 3);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie = Trie::new();
+        trie.insert("apple");
+        trie.insert("app");
+        trie.insert("apple");
+        assert!(!trie.remove("ap"));
+        assert!(trie.remove("apple"));
+        assert_eq!(trie.count_words("apple"), 1);
+        assert!(trie.remove("apple"));
+        assert!(!trie.contains("apple"));
+        assert!(!trie.starts_with("appl"));
+        assert!(trie.contains("app"));
+        assert!(!trie.remove("apple"));
+        assert!(trie.remove("app"));
+        assert!(trie.is_empty());
+        assert!(trie.root.children.is_empty());
+    }
+
+    #[test]
+    fn test_words_with_prefix() {
+        let mut trie = Trie::new();
+        for word in ["car", "cat", "cart", "dog", "ca", "cat"] {
+            trie.insert(word);
+        }
+        assert_eq!(trie.words_with_prefix("ca"), vec!["ca", "car", "cart", "cat"]);
+        assert_eq!(trie.words_with_prefix(""), vec!["ca", "car", "cart", "cat", "dog"]);
+        assert!(trie.words_with_prefix("x").is_empty());
+    }
+}
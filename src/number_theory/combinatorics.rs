@@ -0,0 +1,190 @@
+/*
+    Combinatorics
+    --------------------------------------------------------------
+    Factorials and inverse factorials modulo the prime `M` for every
+    value up to `n`, the inverse factorials come from one modular
+    inverse of n! followed by (i - 1)!^-1 = i!^-1 * i.
+    Lucas' theorem extends nCr to any n when the table covers
+    0..M, i.e. for small prime moduli.
+    Precomputation: O(n), every query: O(1), Lucas: O(log_M n)
+    --------------------------------------------------------------
+ */
+use crate::number_theory::mod_int::ModInt;
+
+pub struct Combinatorics<const M: u64> {
+    fact: Vec<ModInt<M>>,
+    inv_fact: Vec<ModInt<M>>,
+}
+
+#[allow(dead_code)]
+impl<const M: u64> Combinatorics<M> {
+    /// # Arguments
+    ///
+    /// * `n`: Largest value the factorials are needed for, it has to be below the prime `M`
+    ///
+    /// returns: Combinatorics<M>
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let comb = Combinatorics::<998_244_353>::new(200_000);
+    /// let ways = comb.ncr(10, 3); // 120
+    /// ```
+    pub fn new(n: usize) -> Self {
+        assert!((n as u64) < M, "factorials up to {} are not invertible modulo {}", n, M);
+        let mut fact = vec![ModInt::new(1); n + 1];
+        for i in 1..=n {
+            fact[i] = fact[i - 1] * ModInt::from(i);
+        }
+        let mut inv_fact = vec![ModInt::new(1); n + 1];
+        inv_fact[n] = fact[n].inv();
+        for i in (1..=n).rev() {
+            inv_fact[i - 1] = inv_fact[i] * ModInt::from(i);
+        }
+        Combinatorics { fact, inv_fact }
+    }
+
+    /// returns: the largest `n` the table covers
+    pub fn limit(&self) -> usize {
+        self.fact.len() - 1
+    }
+
+    pub fn fact(&self, n: usize) -> ModInt<M> {
+        self.fact[n]
+    }
+
+    pub fn inv_fact(&self, n: usize) -> ModInt<M> {
+        self.inv_fact[n]
+    }
+
+    /// returns: `1 / n` for `1 <= n <= limit()` without a modular exponentiation
+    pub fn inv(&self, n: usize) -> ModInt<M> {
+        debug_assert!(n >= 1, "0 has no inverse");
+        self.inv_fact[n] * self.fact[n - 1]
+    }
+
+    /// returns: the number of ways to choose `r` of `n` items, 0 when `r > n`
+    pub fn ncr(&self, n: usize, r: usize) -> ModInt<M> {
+        if r > n {
+            return ModInt::default();
+        }
+        self.fact[n] * self.inv_fact[r] * self.inv_fact[n - r]
+    }
+
+    /// returns: the number of ordered arrangements of `r` of `n` items, 0 when `r > n`
+    pub fn npr(&self, n: usize, r: usize) -> ModInt<M> {
+        if r > n {
+            return ModInt::default();
+        }
+        self.fact[n] * self.inv_fact[n - r]
+    }
+
+    /// returns: the number of multisets of size `k` over `n` kinds, `C(n + k - 1, k)`
+    pub fn multichoose(&self, n: usize, k: usize) -> ModInt<M> {
+        if n == 0 {
+            return ModInt::new((k == 0) as u64);
+        }
+        self.ncr(n + k - 1, k)
+    }
+
+    /// returns: the `n`-th Catalan number `C(2n, n) / (n + 1) = C(2n, n) - C(2n, n + 1)`, needs `2n <= limit()`
+    pub fn catalan(&self, n: usize) -> ModInt<M> {
+        self.ncr(2 * n, n) - self.ncr(2 * n, n + 1)
+    }
+
+    /// `C(n, r)` for any `n` by Lucas' theorem, the product of `C(n_i, r_i)` over the base `M` digits
+    ///
+    /// The table has to cover every digit, build it with `Combinatorics::<M>::new(M - 1)`
+    pub fn ncr_lucas(&self, n: u64, r: u64) -> ModInt<M> {
+        debug_assert!(self.limit() as u64 == M - 1, "ncr_lucas needs the table up to M - 1");
+        let (mut n, mut r) = (n, r);
+        let mut ans = ModInt::new(1);
+        while r > 0 {
+            ans *= self.ncr((n % M) as usize, (r % M) as usize);
+            n /= M;
+            r /= M;
+        }
+        ans
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    type Mint = ModInt<998_244_353>;
+
+    /// returns: C(n, r) mod m from Pascal's triangle
+    fn pascal(n: usize, m: u64) -> Vec<Vec<u64>> {
+        let mut c = vec![vec![0; n + 1]; n + 1];
+        for i in 0..=n {
+            c[i][0] = 1 % m;
+            for j in 1..=i {
+                c[i][j] = (c[i - 1][j - 1] + c[i - 1][j]) % m;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_combinatorics() {
+        let comb = Combinatorics::<998_244_353>::new(100);
+        assert_eq!(comb.ncr(10, 3), Mint::from(120));
+        assert_eq!(comb.ncr(3, 10), Mint::from(0));
+        assert_eq!(comb.npr(10, 3), Mint::from(720));
+        assert_eq!(comb.multichoose(3, 2), Mint::from(6));
+        assert_eq!(comb.multichoose(0, 0), Mint::from(1));
+        assert_eq!(comb.multichoose(0, 4), Mint::from(0));
+        let catalan: Vec<u64> = (0..10).map(|n| comb.catalan(n).value()).collect();
+        assert_eq!(catalan, vec![1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862]);
+        // only C(2n, n) has to be in the table
+        assert_eq!(Combinatorics::<998_244_353>::new(0).catalan(0), Mint::from(1));
+        assert_eq!(Combinatorics::<998_244_353>::new(6).catalan(3), Mint::from(5));
+        assert_eq!(comb.inv(7) * Mint::from(7), Mint::from(1));
+        assert_eq!(comb.fact(5) * comb.inv_fact(5), Mint::from(1));
+    }
+
+    #[test]
+    fn test_against_pascal() {
+        let n = 60;
+        let comb = Combinatorics::<998_244_353>::new(n);
+        let c = pascal(n, 998_244_353);
+        for i in 0..=n {
+            for j in 0..=n {
+                assert_eq!(comb.ncr(i, j).value(), if j <= i { c[i][j] } else { 0 });
+            }
+        }
+    }
+
+    fn ncr_lucas_prop(n: u64, r: u64) {
+        let c = pascal(n as usize, 7);
+        let comb = Combinatorics::<7>::new(6);
+        assert_eq!(comb.ncr_lucas(n, r).value(), if r <= n { c[n as usize][r as usize] } else { 0 });
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn ncr_lucas(
+            n in 0u64..400,
+            r in 0u64..400,
+        ) {
+            ncr_lucas_prop(n, r);
+        }
+    }
+
+    #[test]
+    fn test_lucas_huge_n() {
+        let comb = Combinatorics::<13>::new(12);
+        // digits in base 13: C(13^16 + 5, 13^16 + 2) = C(1, 1) * C(5, 2)
+        assert_eq!(comb.ncr_lucas(1_000_000_000_000_000_000, 0).value(), 1);
+        assert_eq!(comb.ncr_lucas(13u64.pow(16), 1).value(), 0);
+        assert_eq!(comb.ncr_lucas(13u64.pow(16) + 5, 13u64.pow(16) + 2).value(), 10);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,230 @@
+/*
+    Discrete Log, Primitive Root and Modular Square Root
+    --------------------------------------------------------------
+      * discrete_log: smallest x >= 0 with a^x = b (mod m) by
+        baby-step giant-step, the common factors of a and m are
+        divided out first so m need not be prime
+        Time: O(sqrt(m)) with a HashMap of sqrt(m) entries
+      * primitive_root: smallest generator of (Z/mZ)*, it exists
+        for m = 1, 2, 4, p^k and 2p^k
+        Time: O(factorization of m and phi(m)) + a few pow per candidate
+      * sqrt_mod: Tonelli-Shanks square root modulo a prime
+        Time: O(log^2 p)
+    --------------------------------------------------------------
+ */
+use crate::number_theory::gcd::gcd_u64;
+use crate::number_theory::pollard_rho::{factorize, mul_mod, pow_mod};
+use std::collections::HashMap;
+
+/// # Arguments
+///
+/// * `a`, `b`: Base and target, taken modulo `m`
+/// * `m`: Any positive modulus
+///
+/// returns: the smallest x >= 0 with `a^x = b (mod m)`, None if there is none
+///
+/// # Examples
+///
+/// ```
+/// let x = discrete_log(3, 13, 17); // Some(4), 3^4 = 81 = 13 (mod 17)
+/// let x = discrete_log(2, 3, 8); // None
+/// ```
+#[allow(dead_code)]
+pub fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    debug_assert!(m >= 1, "modulus has to be positive");
+    let (mut a, mut b, mut m) = (a % m, b % m, m);
+    // solve coefficient * a^x = b (mod m) after dividing out gcd(a, m) `offset` times
+    let (mut coefficient, mut offset) = (1 % m, 0);
+    loop {
+        if coefficient == b {
+            return Some(offset);
+        }
+        let g = gcd_u64(a, m);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        b /= g;
+        m /= g;
+        offset += 1;
+        coefficient = mul_mod(coefficient, a / g, m);
+        a %= m;
+    }
+    let n = (m as f64).sqrt() as u64 + 1;
+    // baby steps: b * a^j for j in [0, n), later j overwrite earlier ones so x = n * i - j is the smallest
+    let mut baby_steps = HashMap::with_capacity(n as usize);
+    let mut current = b;
+    for j in 0..n {
+        baby_steps.insert(current, j);
+        current = mul_mod(current, a, m);
+    }
+    // giant steps: coefficient * a^(n * i) for i in [1, n]
+    let giant = pow_mod(a, n, m);
+    let mut current = coefficient;
+    for i in 1..=n {
+        current = mul_mod(current, giant, m);
+        if let Some(&j) = baby_steps.get(&current) {
+            return Some(n * i - j + offset);
+        }
+    }
+    None
+}
+
+/// returns: the smallest primitive root modulo `m`, None if `m` has none
+///
+/// # Examples
+///
+/// ```
+/// let g = primitive_root(998_244_353); // Some(3)
+/// let g = primitive_root(8); // None
+/// ```
+#[allow(dead_code)]
+pub fn primitive_root(m: u64) -> Option<u64> {
+    debug_assert!(m >= 1, "modulus has to be positive");
+    if m <= 2 || m == 4 {
+        return Some(m - 1);
+    }
+    let factors = factorize(m);
+    let odd_factors: Vec<&(u64, u32)> = factors.iter().filter(|&&(p, _)| p != 2).collect();
+    let power_of_two = if factors[0].0 == 2 { factors[0].1 } else { 0 };
+    // only p^k and 2p^k have a cyclic group of units
+    if odd_factors.len() != 1 || power_of_two > 1 {
+        return None;
+    }
+    let (p, k) = *odd_factors[0];
+    let phi = (p - 1) * p.pow(k - 1);
+    let phi_primes: Vec<u64> = factorize(phi).iter().map(|&(q, _)| q).collect();
+    (2..m).find(|&g| gcd_u64(g, m) == 1 && phi_primes.iter().all(|&q| pow_mod(g, phi / q, m) != 1))
+}
+
+/// # Arguments
+///
+/// * `a`: Value to take the square root of, taken modulo `p`
+/// * `p`: A prime
+///
+/// returns: the smaller of the two roots `r` with `r^2 = a (mod p)`, None if `a` is not a quadratic residue
+///
+/// # Examples
+///
+/// ```
+/// let r = sqrt_mod(10, 13); // Some(6), 6^2 = 36 = 10 (mod 13), the other root is 7
+/// let r = sqrt_mod(5, 13); // None
+/// ```
+#[allow(dead_code)]
+pub fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a == 0 || p == 2 {
+        return Some(a);
+    }
+    // Euler's criterion
+    if pow_mod(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+    // p - 1 = q * 2^s with q odd
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| pow_mod(z, (p - 1) / 2, p) == p - 1).unwrap();
+    let mut m = s;
+    let mut c = pow_mod(z, q, p);
+    let mut t = pow_mod(a, q, p);
+    let mut r = pow_mod(a, q.div_ceil(2), p);
+    // invariant: r^2 = a * t with t of order dividing 2^(m - 1)
+    while t != 1 {
+        let mut i = 0;
+        let mut t_power = t;
+        while t_power != 1 {
+            t_power = mul_mod(t_power, t_power, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    Some(r.min(p - r))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::sieve_of_eratosthenes::sieve_of_eratosthenes;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(3, 13, 17), Some(4));
+        assert_eq!(discrete_log(2, 3, 8), None);
+        assert_eq!(discrete_log(2, 1, 8), Some(0));
+        assert_eq!(discrete_log(2, 0, 8), Some(3));
+        assert_eq!(discrete_log(0, 0, 7), Some(1));
+        assert_eq!(discrete_log(5, 0, 1), Some(0));
+        // moduli above i64::MAX
+        assert_eq!(discrete_log(2, 0, 1 << 63), Some(63));
+        assert_eq!(discrete_log(6, 1 << 62, 1 << 63), Some(62));
+        assert_eq!(discrete_log(2, 3, 1 << 63), None);
+        assert_eq!(discrete_log(3, pow_mod(3, 123_456_789, 998_244_353), 998_244_353), Some(123_456_789));
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        assert_eq!(primitive_root(998_244_353), Some(3));
+        assert_eq!(primitive_root(1_000_000_007), Some(5));
+        assert_eq!(primitive_root(8), None);
+        assert_eq!(primitive_root(15), None);
+        assert_eq!(primitive_root(2 * 49), Some(3));
+        assert_eq!(primitive_root(1), Some(0));
+        assert_eq!(primitive_root(2), Some(1));
+        assert_eq!(primitive_root(4), Some(3));
+    }
+
+    #[test]
+    fn test_sqrt_mod() {
+        assert_eq!(sqrt_mod(10, 13), Some(6));
+        assert_eq!(sqrt_mod(5, 13), None);
+        assert_eq!(sqrt_mod(0, 13), Some(0));
+        // p - 1 = 119 * 2^23, many Tonelli-Shanks rounds
+        let p = 998_244_353;
+        let a = mul_mod(123_456_789, 123_456_789, p);
+        assert_eq!(sqrt_mod(a, p), Some(123_456_789.min(p - 123_456_789)));
+        assert_eq!(sqrt_mod(3, p), None);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for p in sieve_of_eratosthenes(200) {
+            let p = p as u64;
+            for a in 0..p {
+                let expected = (0..p).find(|&r| r * r % p == a);
+                assert_eq!(sqrt_mod(a, p), expected, "sqrt({}) mod {}", a, p);
+            }
+        }
+        for m in 1..=200u64 {
+            let phi = (1..=m).filter(|&k| gcd_u64(k, m) == 1).count() as u64;
+            let order = |g: u64| (1..=m).find(|&e| pow_mod(g, e, m) == 1 % m);
+            let expected = (0..m).find(|&g| gcd_u64(g, m) == 1 && order(g) == Some(phi));
+            assert_eq!(primitive_root(m), expected, "primitive root of {}", m);
+        }
+    }
+
+    fn discrete_log_prop(a: u64, b: u64, m: u64) {
+        // a^x mod m is periodic after at most m steps, so x < 2m covers every reachable value
+        let expected = (0..2 * m).find(|&x| pow_mod(a, x, m) == b % m);
+        assert_eq!(discrete_log(a, b, m), expected);
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn discrete_log_small(a in 0u64..300, b in 0u64..300, m in 1u64..300) {
+            discrete_log_prop(a, b, m);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,539 @@
+/*
+    Dynamic ModInt
+    --------------------------------------------------------------
+    ModInt for a modulus that is only known at runtime, any modulus
+    in [1, 2^63) works and products never overflow.
+      * Barrett: multiplies with a precomputed floor(2^128 / m)
+        instead of a hardware u128 `%`
+      * Montgomery: keeps values as a * 2^64 mod m, odd moduli only
+    `DynamicModInt` / `MontgomeryModInt` are value types on top of
+    them, the modulus lives in a thread local and is set once with
+    `set_modulus` before any value is created.
+    Multiplication: O(1), a few 64-bit multiplications
+    --------------------------------------------------------------
+ */
+use crate::number_theory::gcd::mod_inverse_u64;
+use std::cell::Cell;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// returns: the high 128 bits of the 256-bit product `a * b`
+fn mul_high(a: u128, b: u128) -> u128 {
+    let (a_high, a_low) = (a >> 64, a as u64 as u128);
+    let (b_high, b_low) = (b >> 64, b as u64 as u128);
+    let low = a_low * b_low;
+    let middle_1 = a_low * b_high;
+    let middle_2 = a_high * b_low;
+    let carry = ((low >> 64) + (middle_1 as u64 as u128) + (middle_2 as u64 as u128)) >> 64;
+    a_high * b_high + (middle_1 >> 64) + (middle_2 >> 64) + carry
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Barrett {
+    modulo: u64,
+    // floor((2^128 - 1) / modulo)
+    inverse: u128,
+}
+
+#[allow(dead_code)]
+impl Barrett {
+    pub const fn new(modulo: u64) -> Self {
+        assert!(modulo >= 1 && modulo < 1 << 63, "Barrett supports moduli in [1, 2^63)");
+        Barrett { modulo, inverse: u128::MAX / modulo as u128 }
+    }
+    pub fn modulo(&self) -> u64 {
+        self.modulo
+    }
+    /// returns: `z mod m`
+    pub fn reduce(&self, z: u128) -> u64 {
+        // q never exceeds z / m and misses it by at most 2
+        let q = mul_high(z, self.inverse);
+        let mut r = z - q * self.modulo as u128;
+        while r >= self.modulo as u128 {
+            r -= self.modulo as u128;
+        }
+        r as u64
+    }
+    /// returns: `a * b mod m` for `a, b` in [0, m)
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+    pub fn pow(&self, a: u64, exp: u64) -> u64 {
+        let (mut base, mut exp) = (a % self.modulo, exp);
+        let mut ans = 1 % self.modulo;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ans = self.mul(ans, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        ans
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Montgomery {
+    modulo: u64,
+    // -modulo^-1 mod 2^64
+    negative_inverse: u64,
+    // 2^128 mod modulo, turns a into a * 2^64 with one reduction
+    r2: u64,
+}
+
+#[allow(dead_code)]
+impl Montgomery {
+    pub const fn new(modulo: u64) -> Self {
+        assert!(modulo % 2 == 1 && modulo < 1 << 63, "Montgomery supports odd moduli below 2^63");
+        // Newton's iteration, every step doubles the number of correct low bits (modulo is correct to 3)
+        let mut inverse = modulo;
+        let mut i = 0;
+        while i < 5 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(modulo.wrapping_mul(inverse)));
+            i += 1;
+        }
+        let r = ((1u128 << 64) % modulo as u128) as u64;
+        Montgomery {
+            modulo,
+            negative_inverse: inverse.wrapping_neg(),
+            r2: (r as u128 * r as u128 % modulo as u128) as u64,
+        }
+    }
+    pub fn modulo(&self) -> u64 {
+        self.modulo
+    }
+    /// returns: `t * 2^-64 mod m` for `t < m * 2^64`
+    pub fn reduce(&self, t: u128) -> u64 {
+        let u = (t as u64).wrapping_mul(self.negative_inverse);
+        // t + u * m is divisible by 2^64 and stays below 2^128 since m < 2^63
+        let r = ((t + u as u128 * self.modulo as u128) >> 64) as u64;
+        if r >= self.modulo { r - self.modulo } else { r }
+    }
+    /// returns: the Montgomery form `a * 2^64 mod m` of `a`
+    pub fn to_montgomery(&self, a: u64) -> u64 {
+        self.reduce((a % self.modulo) as u128 * self.r2 as u128)
+    }
+    /// returns: `a` from its Montgomery form
+    pub fn from_montgomery(&self, a: u64) -> u64 {
+        self.reduce(a as u128)
+    }
+    /// returns: the Montgomery form of the product, `a` and `b` are in Montgomery form
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        self.reduce(a as u128 * b as u128)
+    }
+    pub fn pow(&self, a: u64, exp: u64) -> u64 {
+        let (mut base, mut exp) = (self.to_montgomery(a), exp);
+        let mut ans = self.to_montgomery(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ans = self.mul(ans, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        self.from_montgomery(ans)
+    }
+}
+
+thread_local! {
+    static BARRETT: Cell<Barrett> = const { Cell::new(Barrett::new(998_244_353)) };
+    static MONTGOMERY: Cell<Montgomery> = const { Cell::new(Montgomery::new(998_244_353)) };
+}
+
+/// ModInt whose modulus is set at runtime, backed by Barrett reduction (the default modulus is 998244353)
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DynamicModInt {
+    value: u64,
+}
+
+#[allow(dead_code)]
+impl DynamicModInt {
+    /// Sets the modulus of every `DynamicModInt` on this thread, values created before are invalidated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// DynamicModInt::set_modulus(scanner.next());
+    /// let x: DynamicModInt = scanner.next();
+    /// println!("{}", x.pow(10) * x.inv());
+    /// ```
+    pub fn set_modulus(modulo: u64) {
+        BARRETT.with(|barrett| barrett.set(Barrett::new(modulo)));
+    }
+    pub fn modulus() -> u64 {
+        BARRETT.with(|barrett| barrett.get().modulo)
+    }
+    fn __from_reduced(value: u64) -> Self {
+        DynamicModInt { value }
+    }
+    fn __mul(self, rhs: Self) -> Self {
+        DynamicModInt { value: BARRETT.with(|barrett| barrett.get().mul(self.value, rhs.value)) }
+    }
+    /// returns: the representative in [0, m)
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+/// ModInt whose odd modulus is set at runtime, backed by Montgomery multiplication (the default modulus is 998244353)
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MontgomeryModInt {
+    // Montgomery form, x * 2^64 mod m
+    value: u64,
+}
+
+#[allow(dead_code)]
+impl MontgomeryModInt {
+    /// Sets the modulus of every `MontgomeryModInt` on this thread, values created before are invalidated
+    pub fn set_modulus(modulo: u64) {
+        MONTGOMERY.with(|montgomery| montgomery.set(Montgomery::new(modulo)));
+    }
+    pub fn modulus() -> u64 {
+        MONTGOMERY.with(|montgomery| montgomery.get().modulo)
+    }
+    fn __from_reduced(value: u64) -> Self {
+        MontgomeryModInt { value: MONTGOMERY.with(|montgomery| montgomery.get().to_montgomery(value)) }
+    }
+    fn __mul(self, rhs: Self) -> Self {
+        MontgomeryModInt { value: MONTGOMERY.with(|montgomery| montgomery.get().mul(self.value, rhs.value)) }
+    }
+    /// returns: the representative in [0, m)
+    pub fn value(&self) -> u64 {
+        MONTGOMERY.with(|montgomery| montgomery.get().from_montgomery(self.value))
+    }
+}
+
+// Everything except multiplication and conversion is shared: both representations are closed under + and - mod m
+macro_rules! dynamic_mod_int_impl {
+    ($($t:ident),*) => {
+        $(
+            #[allow(dead_code)]
+            impl $t {
+                pub fn new(value: u64) -> Self {
+                    $t::__from_reduced(value % $t::modulus())
+                }
+                /// returns: `self^exp` by binary exponentiation
+                pub fn pow(&self, exp: u64) -> Self {
+                    let mut base = *self;
+                    let mut exp = exp;
+                    let mut ans = $t::new(1);
+                    while exp > 0 {
+                        if exp & 1 == 1 {
+                            ans *= base;
+                        }
+                        base *= base;
+                        exp >>= 1;
+                    }
+                    ans
+                }
+                /// returns: the multiplicative inverse, None if `gcd(self, m) != 1`
+                pub fn checked_inv(&self) -> Option<Self> {
+                    mod_inverse_u64(self.value(), $t::modulus()).map($t::__from_reduced)
+                }
+                /// returns: the multiplicative inverse, panics if `gcd(self, m) != 1`
+                pub fn inv(&self) -> Self {
+                    match self.checked_inv() {
+                        Some(inverse) => inverse,
+                        None => panic!("{} has no inverse modulo {}", self.value(), $t::modulus()),
+                    }
+                }
+            }
+
+            impl From<u64> for $t {
+                fn from(value: u64) -> Self {
+                    $t::new(value)
+                }
+            }
+
+            impl From<u32> for $t {
+                fn from(value: u32) -> Self {
+                    $t::new(value as u64)
+                }
+            }
+
+            impl From<usize> for $t {
+                fn from(value: usize) -> Self {
+                    $t::new(value as u64)
+                }
+            }
+
+            /// negative values wrap around
+            impl From<i64> for $t {
+                fn from(value: i64) -> Self {
+                    $t::__from_reduced((value as i128).rem_euclid($t::modulus() as i128) as u64)
+                }
+            }
+
+            impl From<i32> for $t {
+                fn from(value: i32) -> Self {
+                    $t::from(value as i64)
+                }
+            }
+
+            impl Add for $t {
+                type Output = Self;
+                fn add(self, rhs: Self) -> Self {
+                    let m = $t::modulus();
+                    let value = self.value + rhs.value;
+                    $t { value: if value >= m { value - m } else { value } }
+                }
+            }
+
+            impl Sub for $t {
+                type Output = Self;
+                fn sub(self, rhs: Self) -> Self {
+                    let value = if self.value >= rhs.value { self.value - rhs.value } else { self.value + $t::modulus() - rhs.value };
+                    $t { value }
+                }
+            }
+
+            impl Mul for $t {
+                type Output = Self;
+                fn mul(self, rhs: Self) -> Self {
+                    self.__mul(rhs)
+                }
+            }
+
+            impl Div for $t {
+                type Output = Self;
+                #[allow(clippy::suspicious_arithmetic_impl)]
+                fn div(self, rhs: Self) -> Self {
+                    self * rhs.inv()
+                }
+            }
+
+            impl Neg for $t {
+                type Output = Self;
+                fn neg(self) -> Self {
+                    $t::default() - self
+                }
+            }
+
+            impl AddAssign for $t {
+                fn add_assign(&mut self, rhs: Self) {
+                    *self = *self + rhs;
+                }
+            }
+
+            impl SubAssign for $t {
+                fn sub_assign(&mut self, rhs: Self) {
+                    *self = *self - rhs;
+                }
+            }
+
+            impl MulAssign for $t {
+                fn mul_assign(&mut self, rhs: Self) {
+                    *self = *self * rhs;
+                }
+            }
+
+            impl DivAssign for $t {
+                fn div_assign(&mut self, rhs: Self) {
+                    *self = *self / rhs;
+                }
+            }
+
+            impl Sum for $t {
+                fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+                    iter.fold($t::default(), |a, b| a + b)
+                }
+            }
+
+            impl Product for $t {
+                fn product<I: Iterator<Item=Self>>(iter: I) -> Self {
+                    iter.fold($t::new(1), |a, b| a * b)
+                }
+            }
+
+            impl fmt::Display for $t {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self.value())
+                }
+            }
+
+            impl fmt::Debug for $t {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self.value())
+                }
+            }
+
+            impl FromStr for $t {
+                type Err = std::num::ParseIntError;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    let value = s.trim().parse::<i128>()?;
+                    Ok($t::__from_reduced(value.rem_euclid($t::modulus() as i128) as u64))
+                }
+            }
+        )*
+    };
+}
+
+dynamic_mod_int_impl!(DynamicModInt, MontgomeryModInt);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::modulo_arithmetic::ModuloArithmetic;
+    use std::time::Instant;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_dynamic_mod_int() {
+        DynamicModInt::set_modulus(1_000_000_007);
+        let a = DynamicModInt::from(5);
+        let b = DynamicModInt::from(-3);
+        assert_eq!(b.value(), 1_000_000_004);
+        assert_eq!((a * b).value(), 999_999_992);
+        assert_eq!((a / b * b), a);
+        assert_eq!(DynamicModInt::from(2).pow(10).value(), 1024);
+        assert_eq!("-1".parse::<DynamicModInt>().unwrap().to_string(), "1000000006");
+
+        DynamicModInt::set_modulus(12);
+        assert_eq!(DynamicModInt::from(5).inv().value(), 5);
+        assert_eq!(DynamicModInt::from(4).checked_inv(), None);
+    }
+
+    #[test]
+    fn test_montgomery_mod_int() {
+        MontgomeryModInt::set_modulus(1_000_000_007);
+        let a = MontgomeryModInt::from(5);
+        let b = MontgomeryModInt::from(-3);
+        assert_eq!(b.value(), 1_000_000_004);
+        assert_eq!((a + b).value(), 2);
+        assert_eq!((a * b).value(), 999_999_992);
+        assert_eq!((a / b * b), a);
+        assert_eq!((1..=5).map(MontgomeryModInt::from).product::<MontgomeryModInt>().value(), 120);
+        assert_eq!(MontgomeryModInt::from(-1).to_string(), "1000000006");
+    }
+
+    #[test]
+    fn test_modulus_one() {
+        DynamicModInt::set_modulus(1);
+        assert_eq!((DynamicModInt::from(7) * DynamicModInt::from(3)).value(), 0);
+        assert_eq!(DynamicModInt::from(7).pow(0).value(), 0);
+        MontgomeryModInt::set_modulus(1);
+        assert_eq!((MontgomeryModInt::from(7) * MontgomeryModInt::from(3)).value(), 0);
+    }
+
+    fn reducers_prop(modulo: u64, a: u64, b: u64, exp: u64) {
+        let (a, b) = (a % modulo, b % modulo);
+        let expected = (a as u128 * b as u128 % modulo as u128) as u64;
+        let barrett = std::hint::black_box(Barrett::new(modulo));
+        assert_eq!(barrett.mul(a, b), expected);
+        let mut expected_pow = 1 % modulo as u128;
+        for _ in 0..exp % 64 {
+            expected_pow = expected_pow * a as u128 % modulo as u128;
+        }
+        assert_eq!(barrett.pow(a, exp % 64), expected_pow as u64);
+        if modulo % 2 == 1 {
+            let montgomery = Montgomery::new(modulo);
+            let (x, y) = (montgomery.to_montgomery(a), montgomery.to_montgomery(b));
+            assert_eq!(montgomery.from_montgomery(x), a);
+            assert_eq!(montgomery.from_montgomery(montgomery.mul(x, y)), expected);
+            assert_eq!(montgomery.pow(a, exp % 64), expected_pow as u64);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn reducers(
+            modulo in prop_oneof![1u64..1000, 1u64..1 << 63, (1u64 << 62)..1 << 63],
+            a in any::<u64>(),
+            b in any::<u64>(),
+            exp in any::<u64>(),
+        ) {
+            reducers_prop(modulo, a, b, exp);
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with cargo test --release -- --ignored"]
+    fn test_dynamic_mod_int_time() {
+        const N: usize = 10_000_000;
+        const MODULO: i64 = 1_000_000_007;
+        let a: Vec<u64> = (0..1024).map(|_| rand::random::<u64>() % MODULO as u64).collect();
+
+        let start = Instant::now();
+        let modulo_arithmetic = ModuloArithmetic::new(MODULO);
+        let mut expected = 1;
+        for i in 0..N {
+            expected = modulo_arithmetic.mul(expected, a[i & 1023] as i64);
+        }
+        let modulo_arithmetic_time = start.elapsed();
+
+        let start = Instant::now();
+        // black_box keeps the compiler from folding the precomputed inverse into the loop
+        let barrett = std::hint::black_box(Barrett::new(MODULO as u64));
+        let mut barrett_result = 1;
+        for i in 0..N {
+            barrett_result = barrett.mul(barrett_result, a[i & 1023]);
+        }
+        let barrett_time = start.elapsed();
+
+        let start = Instant::now();
+        let montgomery = Montgomery::new(MODULO as u64);
+        let b: Vec<u64> = a.iter().map(|&x| montgomery.to_montgomery(x)).collect();
+        let mut montgomery_result = montgomery.to_montgomery(1);
+        for i in 0..N {
+            montgomery_result = montgomery.mul(montgomery_result, b[i & 1023]);
+        }
+        let montgomery_result = montgomery.from_montgomery(montgomery_result);
+        let montgomery_time = start.elapsed();
+
+        // the thread local modulus lookup costs about as much as the `%` it saves, only the result is checked
+        DynamicModInt::set_modulus(MODULO as u64);
+        let c: Vec<DynamicModInt> = a.iter().map(|&x| DynamicModInt::new(x)).collect();
+        let mut dynamic_result = DynamicModInt::new(1);
+        for i in 0..N {
+            dynamic_result *= c[i & 1023];
+        }
+
+        assert_eq!(barrett_result as i64, expected);
+        assert_eq!(montgomery_result as i64, expected);
+        assert_eq!(dynamic_result.value() as i64, expected);
+        assert!(barrett_time < modulo_arithmetic_time, "Barrett {:?}, ModuloArithmetic {:?}", barrett_time, modulo_arithmetic_time);
+        assert!(montgomery_time < modulo_arithmetic_time, "Montgomery {:?}, ModuloArithmetic {:?}", montgomery_time, modulo_arithmetic_time);
+
+        // moduli above 3 * 10^9 overflow ModuloArithmetic, compare against a plain u128 `%` instead
+        let modulo = (1u64 << 62) + 135;
+        let a: Vec<u64> = (0..1024).map(|_| rand::random::<u64>() % modulo).collect();
+
+        let start = Instant::now();
+        let mut expected = 1u64;
+        for i in 0..N {
+            expected = (expected as u128 * a[i & 1023] as u128 % modulo as u128) as u64;
+        }
+        let u128_time = start.elapsed();
+
+        let start = Instant::now();
+        let barrett = std::hint::black_box(Barrett::new(modulo));
+        let mut barrett_result = 1;
+        for i in 0..N {
+            barrett_result = barrett.mul(barrett_result, a[i & 1023]);
+        }
+        let barrett_time = start.elapsed();
+
+        let start = Instant::now();
+        let montgomery = Montgomery::new(modulo);
+        let b: Vec<u64> = a.iter().map(|&x| montgomery.to_montgomery(x)).collect();
+        let mut montgomery_result = montgomery.to_montgomery(1);
+        for i in 0..N {
+            montgomery_result = montgomery.mul(montgomery_result, b[i & 1023]);
+        }
+        let montgomery_result = montgomery.from_montgomery(montgomery_result);
+        let montgomery_time = start.elapsed();
+
+        assert_eq!(barrett_result, expected);
+        assert_eq!(montgomery_result, expected);
+        assert!(barrett_time < u128_time, "Barrett {:?}, u128 % {:?}", barrett_time, u128_time);
+        assert!(montgomery_time < u128_time, "Montgomery {:?}, u128 % {:?}", montgomery_time, u128_time);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+    GCD, Extended Euclid and CRT
+    --------------------------------------------------------------
+    Everything built on the extended Euclidean algorithm, the single
+    home of gcd and modular inverses for the other modules. Inputs
+    may be negative, the Bezout coefficients are bounded by the
+    inputs so ext_gcd never overflows.
+      * gcd / gcd_u64: the same loop for signed and unsigned inputs
+      * ext_gcd / mod_inverse: inverses for any modulus coprime with a,
+        mod_inverse_u64 runs in i128 for moduli up to 2^64
+      * crt: merges congruences pairwise, moduli need not be coprime,
+        None when they contradict each other, the running remainder
+        and lcm are i128 and the lcm has to fit in i64
+      * solve_linear_congruence: all x in [0, m) with a * x = b (mod m)
+    Time Complexity: O(log min(a, b)) per gcd
+    --------------------------------------------------------------
+ */
+
+/// returns: the greatest common divisor, `gcd_u64(0, 0) = 0`
+#[allow(dead_code)]
+pub fn gcd_u64(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// returns: the non-negative greatest common divisor, `gcd(0, 0) = 0`
+#[allow(dead_code)]
+pub fn gcd(a: i64, b: i64) -> i64 {
+    gcd_u64(a.unsigned_abs(), b.unsigned_abs()) as i64
+}
+
+/// returns: the non-negative least common multiple, 0 if either is 0
+#[allow(dead_code)]
+pub fn lcm(a: i64, b: i64) -> i64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// # Arguments
+///
+/// * `a`, `b`: Any i64
+///
+/// returns: `(g, x, y)` with `a * x + b * y = g = gcd(a, b)` and `g >= 0`
+///
+/// # Examples
+///
+/// ```
+/// let (g, x, y) = ext_gcd(240, 46); // (2, -9, 47), 240 * -9 + 46 * 47 = 2
+/// ```
+#[allow(dead_code)]
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
+/// returns: `a^-1 mod m` in [0, m), None if `gcd(a, m) != 1`
+#[allow(dead_code)]
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    debug_assert!(m >= 1, "modulus has to be positive, got {}", m);
+    mod_inverse_u64(a.rem_euclid(m) as u64, m as u64).map(|x| x as i64)
+}
+
+/// returns: `a^-1 mod m` in [0, m) for any u64 modulus, None if `gcd(a, m) != 1`
+#[allow(dead_code)]
+pub fn mod_inverse_u64(a: u64, m: u64) -> Option<u64> {
+    debug_assert!(m >= 1, "modulus has to be positive");
+    let (mut old_r, mut r) = ((a % m) as i128, m as i128);
+    let (mut old_x, mut x) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_x.rem_euclid(m as i128) as u64)
+}
+
+/// # Arguments
+///
+/// * `congruences`: `(remainder, modulus)` pairs, every modulus positive
+///
+/// returns: `(r, l)` such that the system is equivalent to `x = r (mod l)` with `l` the lcm of the moduli and
+/// `r` in [0, l), None if no x satisfies every congruence
+///
+/// # Examples
+///
+/// ```
+/// let merged = crt(&[(2, 3), (3, 5), (2, 7)]); // Some((23, 105))
+/// let merged = crt(&[(1, 4), (3, 6)]); // Some((9, 12))
+/// let merged = crt(&[(1, 4), (2, 6)]); // None, x would be both odd and even
+/// ```
+#[allow(dead_code)]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut r, mut l) = (0i128, 1i128);
+    for &(remainder, modulus) in congruences {
+        debug_assert!(modulus >= 1, "modulus has to be positive, got {}", modulus);
+        let (r2, m2) = ((remainder as i128).rem_euclid(modulus as i128), modulus as i128);
+        // l * k = r2 - r (mod m2) is solvable iff g divides r2 - r
+        let (g, p, _) = ext_gcd(l as i64, m2 as i64);
+        let (g, p) = (g as i128, p as i128);
+        if (r2 - r) % g != 0 {
+            return None;
+        }
+        let k = ((r2 - r) / g % (m2 / g) * p).rem_euclid(m2 / g);
+        r += l * k;
+        l = l / g * m2;
+        assert!(l <= i64::MAX as i128, "the lcm of the moduli does not fit in i64");
+    }
+    Some((r as i64, l as i64))
+}
+
+/// returns: every x in [0, m) with `a * x = b (mod m)` in increasing order, there are `gcd(a, m)` of them or none
+///
+/// # Examples
+///
+/// ```
+/// let solutions = solve_linear_congruence(6, 4, 10); // [4, 9]
+/// ```
+#[allow(dead_code)]
+pub fn solve_linear_congruence(a: i64, b: i64, m: i64) -> Vec<i64> {
+    debug_assert!(m >= 1, "modulus has to be positive, got {}", m);
+    let (a, b) = (a.rem_euclid(m), b.rem_euclid(m));
+    let g = gcd(a, m);
+    if b % g != 0 {
+        return Vec::new();
+    }
+    // a/g is invertible modulo m/g, the solutions are x0 + k * m/g
+    let step = m / g;
+    let x0 = ((b / g) as i128 * mod_inverse(a / g, step).unwrap() as i128 % step as i128) as i64;
+    (0..g).map(|k| x0 + k * step).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(0, -5), 5);
+        assert_eq!(gcd_u64(1 << 63, 6), 2);
+        assert_eq!(gcd_u64(u64::MAX, 0), u64::MAX);
+        assert_eq!(lcm(4, -6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        let (g, x, y) = ext_gcd(-240, 46);
+        assert_eq!((g, -240 * x + 46 * y), (2, 2));
+        assert_eq!(ext_gcd(0, 0), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(5, 12), Some(5));
+        assert_eq!(mod_inverse(4, 12), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse_u64(2, u64::MAX), Some(1 << 63));
+        assert_eq!(mod_inverse_u64(3, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        // moduli whose product overflows i64 while the lcm does not
+        let p = 1_000_000_007;
+        let q = 1_000_000_009;
+        assert_eq!(crt(&[(3, p * 2), (3, q * 2), (3, p * q)]), Some((3, 2 * p * q)));
+    }
+
+    #[test]
+    #[should_panic(expected = "the lcm of the moduli does not fit in i64")]
+    fn test_crt_lcm_overflow_panics() {
+        crt(&[(0, 1_000_000_007), (0, 1_000_000_009), (0, 998_244_353)]);
+    }
+
+    #[test]
+    fn test_solve_linear_congruence() {
+        assert_eq!(solve_linear_congruence(6, 4, 10), vec![4, 9]);
+        assert_eq!(solve_linear_congruence(6, 5, 10), vec![]);
+        assert_eq!(solve_linear_congruence(0, 0, 3), vec![0, 1, 2]);
+        assert_eq!(solve_linear_congruence(-1, 1, 5), vec![4]);
+    }
+
+    fn crt_prop(congruences: Vec<(i64, i64)>) {
+        let l = congruences.iter().fold(1, |l, &(_, m)| lcm(l, m));
+        let expected = (0..l).find(|&x| congruences.iter().all(|&(r, m)| (x - r).rem_euclid(m) == 0));
+        assert_eq!(crt(&congruences), expected.map(|x| (x, l)));
+    }
+
+    fn linear_congruence_prop(a: i64, b: i64, m: i64) {
+        let expected: Vec<i64> = (0..m).filter(|&x| (a * x - b).rem_euclid(m) == 0).collect();
+        assert_eq!(solve_linear_congruence(a, b, m), expected);
+        let (g, x, y) = ext_gcd(a, m);
+        assert_eq!(g, gcd(a, m));
+        assert_eq!(a * x + m * y, g);
+        assert_eq!(mod_inverse(a, m), (0..m).find(|&x| (a * x - 1).rem_euclid(m) == 0));
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn crt_small(congruences in prop::collection::vec((-50i64..50, 1i64..=12), 0..=4)) {
+            crt_prop(congruences);
+        }
+
+        #[test]
+        fn linear_congruence(a in -100i64..100, b in -100i64..100, m in 1i64..=60) {
+            linear_congruence_prop(a, b, m);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,232 @@
+/*
+    Linear Sieve
+    --------------------------------------------------------------
+    Sieve in which every composite x is crossed out exactly once,
+    as spf(x) * (x / spf(x)), so it also records the smallest prime
+    factor of every number. The same pass fills the multiplicative
+    functions: for a prime p <= spf(i), f(i * p) follows from f(i)
+    and f(i / p), no exponent table is kept.
+      * phi: Euler's totient
+      * mobius: Möbius function
+      * divisor_count / divisor_sum: number and sum of divisors
+    Time Complexity: O(n), factorize: O(log x)
+    Space Complexity: 21 bytes per number, 4 with `spf_only`
+    --------------------------------------------------------------
+ */
+pub struct LinearSieve {
+    // 0 for 0 and 1
+    spf: Vec<u32>,
+    primes: Vec<usize>,
+    // empty when built with `spf_only`
+    phi: Vec<u32>,
+    mobius: Vec<i8>,
+    divisor_count: Vec<u32>,
+    divisor_sum: Vec<u64>,
+}
+
+#[allow(dead_code)]
+impl LinearSieve {
+    /// # Arguments
+    ///
+    /// * `upper_limit`: Largest number covered by the tables, below 2^32 - 1
+    ///
+    /// returns: LinearSieve
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let sieve = LinearSieve::new(1_000_000);
+    /// let factors = sieve.factorize(360); // [(2, 3), (3, 2), (5, 1)]
+    /// let phi = sieve.phi(360); // 96
+    /// ```
+    pub fn new(upper_limit: usize) -> Self {
+        Self::__sieve(upper_limit, true)
+    }
+
+    /// Same as `new` without the multiplicative function tables, for when only `spf`, `factorize` and the primes are needed
+    pub fn spf_only(upper_limit: usize) -> Self {
+        Self::__sieve(upper_limit, false)
+    }
+
+    fn __sieve(upper_limit: usize, functions: bool) -> Self {
+        assert!(upper_limit < u32::MAX as usize, "LinearSieve stores numbers as u32, {} is too large", upper_limit);
+        let n = upper_limit + 1;
+        let table_len = if functions { n } else { 0 };
+        let mut spf = vec![0u32; n];
+        let mut primes = Vec::new();
+        let mut phi = vec![0u32; table_len];
+        let mut mobius = vec![0; table_len];
+        let mut divisor_count = vec![0; table_len];
+        let mut divisor_sum = vec![0; table_len];
+        if functions && upper_limit >= 1 {
+            phi[1] = 1;
+            mobius[1] = 1;
+            divisor_count[1] = 1;
+            divisor_sum[1] = 1;
+        }
+        for i in 2..n {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                primes.push(i);
+                if functions {
+                    phi[i] = i as u32 - 1;
+                    mobius[i] = -1;
+                    divisor_count[i] = 2;
+                    divisor_sum[i] = i as u64 + 1;
+                }
+            }
+            for &p in &primes {
+                if p > spf[i] as usize || i * p >= n {
+                    break;
+                }
+                let x = i * p;
+                spf[x] = p as u32;
+                if !functions {
+                    continue;
+                }
+                if p == spf[i] as usize {
+                    // p already divides i, only its exponent e grows: d(p^(e + 1)) = 2 d(p^e) - d(p^(e - 1))
+                    // and sigma(p^(e + 1)) = (p + 1) sigma(p^e) - p sigma(p^(e - 1)), the rest of i cancels out
+                    phi[x] = phi[i] * p as u32;
+                    mobius[x] = 0;
+                    divisor_count[x] = 2 * divisor_count[i] - divisor_count[i / p];
+                    divisor_sum[x] = (p as u64 + 1) * divisor_sum[i] - p as u64 * divisor_sum[i / p];
+                } else {
+                    // p is coprime with i
+                    phi[x] = phi[i] * (p as u32 - 1);
+                    mobius[x] = -mobius[i];
+                    divisor_count[x] = divisor_count[i] * 2;
+                    divisor_sum[x] = divisor_sum[i] * (p as u64 + 1);
+                }
+            }
+        }
+        LinearSieve { spf, primes, phi, mobius, divisor_count, divisor_sum }
+    }
+
+    /// returns: the primes up to `upper_limit` in increasing order
+    pub fn primes(&self) -> &Vec<usize> {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, x: usize) -> bool {
+        x >= 2 && self.spf[x] as usize == x
+    }
+
+    /// returns: the smallest prime factor of `x`, 0 for 0 and 1
+    pub fn spf(&self, x: usize) -> usize {
+        self.spf[x] as usize
+    }
+
+    /// returns: the `(prime, exponent)` pairs of `x` in increasing order of prime, empty for 1
+    pub fn factorize(&self, x: usize) -> Vec<(usize, u32)> {
+        debug_assert!(x >= 1, "0 has no factorization");
+        let mut x = x;
+        let mut factors: Vec<(usize, u32)> = Vec::new();
+        while x > 1 {
+            let p = self.spf[x] as usize;
+            match factors.last_mut() {
+                Some((last, e)) if *last == p => *e += 1,
+                _ => factors.push((p, 1)),
+            }
+            x /= p;
+        }
+        factors
+    }
+
+    /// returns: the number of `1 <= k <= x` coprime with `x`
+    pub fn phi(&self, x: usize) -> usize {
+        debug_assert!(!self.phi.is_empty(), "LinearSieve::spf_only has no multiplicative function tables");
+        self.phi[x] as usize
+    }
+
+    /// returns: 0 if `x` has a square factor, otherwise `(-1)^(number of prime factors)`
+    pub fn mobius(&self, x: usize) -> i8 {
+        debug_assert!(!self.mobius.is_empty(), "LinearSieve::spf_only has no multiplicative function tables");
+        self.mobius[x]
+    }
+
+    pub fn divisor_count(&self, x: usize) -> u32 {
+        debug_assert!(!self.divisor_count.is_empty(), "LinearSieve::spf_only has no multiplicative function tables");
+        self.divisor_count[x]
+    }
+
+    pub fn divisor_sum(&self, x: usize) -> u64 {
+        debug_assert!(!self.divisor_sum.is_empty(), "LinearSieve::spf_only has no multiplicative function tables");
+        self.divisor_sum[x]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::sieve_of_eratosthenes::sieve_of_eratosthenes;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_linear_sieve() {
+        let sieve = LinearSieve::new(1000);
+        assert_eq!(sieve.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(sieve.factorize(1), vec![]);
+        assert_eq!(sieve.factorize(997), vec![(997, 1)]);
+        assert_eq!(sieve.phi(360), 96);
+        assert_eq!(sieve.mobius(30), -1);
+        assert_eq!(sieve.mobius(12), 0);
+        assert_eq!(sieve.mobius(1), 1);
+        assert_eq!(sieve.divisor_count(360), 24);
+        assert_eq!(sieve.divisor_sum(360), 1170);
+        assert_eq!(sieve.spf(91), 7);
+        assert!(sieve.is_prime(2) && !sieve.is_prime(1) && !sieve.is_prime(0));
+        assert_eq!(sieve.primes(), &sieve_of_eratosthenes(1000));
+    }
+
+    #[test]
+    fn test_small_limits() {
+        assert!(LinearSieve::new(0).primes().is_empty());
+        let sieve = LinearSieve::new(1);
+        assert_eq!(sieve.phi(1), 1);
+        assert_eq!(sieve.divisor_sum(1), 1);
+    }
+
+    #[test]
+    fn test_spf_only() {
+        let sieve = LinearSieve::spf_only(1000);
+        assert_eq!(sieve.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(sieve.primes(), &sieve_of_eratosthenes(1000));
+        assert!(sieve.is_prime(997) && !sieve.is_prime(91));
+    }
+
+    fn multiplicative_functions_prop(upper_limit: usize) {
+        let sieve = LinearSieve::new(upper_limit);
+        for x in 1..=upper_limit {
+            let divisors: Vec<usize> = (1..=x).filter(|d| x % d == 0).collect();
+            let gcd = |mut a: usize, mut b: usize| {
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                a
+            };
+            let factors = sieve.factorize(x);
+            assert_eq!(factors.iter().map(|&(p, e)| p.pow(e)).product::<usize>(), x);
+            assert!(factors.iter().all(|&(p, _)| sieve.is_prime(p)));
+            assert_eq!(sieve.phi(x), (1..=x).filter(|&k| gcd(k, x) == 1).count());
+            let square_free = factors.iter().all(|&(_, e)| e == 1);
+            let expected_mobius = if square_free { (-1i8).pow(factors.len() as u32) } else { 0 };
+            assert_eq!(sieve.mobius(x), expected_mobius);
+            assert_eq!(sieve.divisor_count(x) as usize, divisors.len());
+            assert_eq!(sieve.divisor_sum(x), divisors.iter().sum::<usize>() as u64);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn multiplicative_functions(upper_limit in 0usize..=300) {
+            multiplicative_functions_prop(upper_limit);
+        }
+    }
+}
\ No newline at end of file
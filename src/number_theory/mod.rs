@@ -1,2 +1,11 @@
 pub mod sieve_of_eratosthenes;
-pub mod modulo_arithmetic;
\ No newline at end of file
+pub mod modulo_arithmetic;
+pub mod mod_int;
+pub mod dynamic_mod_int;
+pub mod combinatorics;
+pub mod linear_sieve;
+pub mod segmented_sieve;
+pub mod pollard_rho;
+pub mod gcd;
+pub mod discrete_log;
+pub mod ntt;
\ No newline at end of file
@@ -0,0 +1,243 @@
+/*
+    ModInt
+    --------------------------------------------------------------
+    Value type for arithmetic modulo the compile time constant `M`,
+    the value is always kept in [0, M). Products go through u128 so
+    any modulus below 2^63 is fine. Division and `inv` need the
+    divisor to be coprime with `M` (any non-zero value for prime M).
+    --------------------------------------------------------------
+ */
+use crate::number_theory::gcd::mod_inverse_u64;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+pub type ModInt998244353 = ModInt<998_244_353>;
+pub type ModInt1000000007 = ModInt<1_000_000_007>;
+
+#[allow(dead_code)]
+impl<const M: u64> ModInt<M> {
+    pub const MODULO: u64 = M;
+
+    /// returns: `value mod M`
+    pub fn new(value: u64) -> Self {
+        ModInt { value: value % M }
+    }
+    /// returns: the representative in [0, M)
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+    /// returns: `self^exp` by binary exponentiation
+    pub fn pow(&self, exp: u64) -> Self {
+        let mut base = *self;
+        let mut exp = exp;
+        let mut ans = ModInt::new(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ans *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        ans
+    }
+    /// returns: the multiplicative inverse, None if `gcd(self, M) != 1`
+    pub fn checked_inv(&self) -> Option<Self> {
+        // extended euclid on (value, M), works for composite moduli too
+        mod_inverse_u64(self.value, M).map(|value| ModInt { value })
+    }
+    /// returns: the multiplicative inverse, panics if `gcd(self, M) != 1`
+    pub fn inv(&self) -> Self {
+        match self.checked_inv() {
+            Some(inverse) => inverse,
+            None => panic!("{} has no inverse modulo {}", self.value, M),
+        }
+    }
+}
+
+macro_rules! mod_int_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl<const M: u64> From<$t> for ModInt<M> {
+                fn from(value: $t) -> Self {
+                    ModInt { value: (value as u128 % M as u128) as u64 }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! mod_int_from_signed {
+    ($($t:ty),*) => {
+        $(
+            /// negative values wrap around, `ModInt::<7>::from(-1) == 6`
+            impl<const M: u64> From<$t> for ModInt<M> {
+                fn from(value: $t) -> Self {
+                    ModInt { value: (value as i128).rem_euclid(M as i128) as u64 }
+                }
+            }
+        )*
+    };
+}
+
+mod_int_from_unsigned!(u8, u16, u32, u64, u128, usize);
+mod_int_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let value = self.value + rhs.value;
+        ModInt { value: if value >= M { value - M } else { value } }
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        let value = if self.value >= rhs.value { self.value - rhs.value } else { self.value + M - rhs.value };
+        ModInt { value }
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        ModInt { value: (self.value as u128 * rhs.value as u128 % M as u128) as u64 }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        ModInt::default() - self
+    }
+}
+
+macro_rules! mod_int_assign_ops {
+    ($($trait:ident $method:ident $op:tt),*) => {
+        $(
+            impl<const M: u64> $trait for ModInt<M> {
+                fn $method(&mut self, rhs: Self) {
+                    *self = *self $op rhs;
+                }
+            }
+        )*
+    };
+}
+
+mod_int_assign_ops!(AddAssign add_assign +, SubAssign sub_assign -, MulAssign mul_assign *, DivAssign div_assign /);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+        iter.fold(ModInt::default(), |a, b| a + b)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item=Self>>(iter: I) -> Self {
+        iter.fold(ModInt::new(1), |a, b| a * b)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<const M: u64> fmt::Debug for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Parses any integer that fits in an i128, so `scanner.next::<ModInt998244353>()` reads big or negative inputs
+impl<const M: u64> FromStr for ModInt<M> {
+    type Err = std::num::ParseIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse::<i128>().map(ModInt::from)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mint = ModInt1000000007;
+    const MODULO: i64 = 1_000_000_007;
+
+    #[test]
+    fn test_mod_int() {
+        let a = Mint::from(5);
+        let b = Mint::from(-3);
+        assert_eq!(b.value(), 1_000_000_004);
+        assert_eq!((a + b).value(), 2);
+        assert_eq!((b - a).value(), 999_999_999);
+        assert_eq!((a * b).value(), 999_999_992);
+        assert_eq!((a / a).value(), 1);
+        assert_eq!((-a).value(), 1_000_000_002);
+        assert_eq!(Mint::from(2).pow(10).value(), 1024);
+        assert_eq!(Mint::from(3).inv() * Mint::from(3), Mint::from(1));
+
+        let mut c = a;
+        c += b;
+        c *= a;
+        c -= Mint::from(1);
+        c /= Mint::from(3);
+        assert_eq!(c, Mint::from(3));
+
+        assert_eq!((1..=5).map(Mint::from).product::<Mint>(), Mint::from(120));
+        assert_eq!((1..=5).map(Mint::from).sum::<Mint>(), Mint::from(15));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("1000000008".parse::<Mint>(), Ok(Mint::from(1)));
+        assert_eq!("-1".parse::<Mint>(), Ok(Mint::from(MODULO - 1)));
+        assert_eq!(" 42\n".parse::<Mint>(), Ok(Mint::from(42)));
+        assert!("x".parse::<Mint>().is_err());
+        assert_eq!(Mint::from(-2).to_string(), "1000000005");
+        assert_eq!(format!("{:?}", ModInt998244353::from(998_244_354u64)), "1");
+    }
+
+    #[test]
+    fn test_composite_modulus() {
+        type Mint12 = ModInt<12>;
+        assert_eq!(Mint12::from(5).inv(), Mint12::from(5));
+        assert_eq!(Mint12::from(4).checked_inv(), None);
+        // products near 2^63 do not overflow
+        type BigMint = ModInt<{ (1 << 62) + 135 }>;
+        let x = BigMint::from(-1);
+        assert_eq!(x * x, BigMint::from(1));
+    }
+
+    #[test]
+    fn test_random_against_i64() {
+        for _ in 0..100_000 {
+            let a = rand::random::<i64>() % MODULO;
+            let b = rand::random::<i64>() % MODULO;
+            let (x, y) = (Mint::from(a), Mint::from(b));
+            assert_eq!((x + y).value() as i64, (a + b).rem_euclid(MODULO));
+            assert_eq!((x - y).value() as i64, (a - b).rem_euclid(MODULO));
+            assert_eq!((x * y).value() as i64, (a * b).rem_euclid(MODULO));
+            if b != 0 {
+                assert_eq!(x / y * y, x);
+            }
+        }
+    }
+}
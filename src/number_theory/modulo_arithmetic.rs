@@ -1,3 +1,5 @@
+use crate::number_theory::gcd::mod_inverse;
+
 // #[allow(dead_code,unused_variables)]
 pub struct ModuloArithmetic {
     modulo: i64,
@@ -6,36 +8,65 @@ pub struct ModuloArithmetic {
 #[allow(dead_code)]
 impl ModuloArithmetic {
     pub fn new(modulo: i64) -> Self {
+        assert!(modulo >= 1, "modulo has to be positive, got {}", modulo);
         Self { modulo }
     }
     pub fn def() -> Self {
         Self { modulo: 1_000_000_007 }
     }
+    /// returns: the representative of `a` in [0, modulo), negative values wrap around
+    pub fn normalize(&self, a: i64) -> i64 {
+        return a.rem_euclid(self.modulo);
+    }
+    /// Every operation accepts any i64 (negative or not reduced) and returns a value in [0, modulo)
     pub fn add(&self, a: i64, b: i64) -> i64 {
-        return (a + b) % self.modulo;
+        // a - modulo + b is in (-modulo, modulo), so nothing overflows even for moduli close to 2^63
+        let sum = self.normalize(a) - self.modulo + self.normalize(b);
+        return if sum < 0 { sum + self.modulo } else { sum };
     }
     pub fn sub(&self, a: i64, b: i64) -> i64 {
-        return (a - b + self.modulo) % self.modulo;
+        let difference = self.normalize(a) - self.normalize(b);
+        return if difference < 0 { difference + self.modulo } else { difference };
     }
     pub fn mul(&self, a: i64, b: i64) -> i64 {
-        return (a * b) % self.modulo;
+        return (self.normalize(a) as i128 * self.normalize(b) as i128 % self.modulo as i128) as i64;
+    }
+    /// returns: the inverse of `b` by the extended Euclidean algorithm, None if `gcd(b, modulo) != 1`
+    pub fn inv(&self, b: i64) -> Option<i64> {
+        return mod_inverse(b, self.modulo);
     }
+    /// returns: `a * b^-1`, works for composite moduli as long as `gcd(b, modulo) == 1`
+    ///
+    /// Panics if `b` has no inverse, use `inv` to check first
     pub fn div(&self, a: i64, b: i64) -> i64 {
-        return self.mul(a, self.bin_pow(b, self.modulo - 2));
+        match self.inv(b) {
+            Some(inverse) => return self.mul(a, inverse),
+            None => panic!("{} has no inverse modulo {}", b, self.modulo),
+        }
     }
+    /// returns: `a^b`, a negative `b` raises the inverse of `a` (which then has to exist)
     pub fn bin_pow(&self, a: i64, b: i64) -> i64 {
-        if b == 0 {
-            return 1;
-        }
-        let half = self.bin_pow(a, b / 2);
-        return if b % 2 == 0 {
-            self.mul(half, half)
+        let mut base = if b < 0 {
+            match self.inv(a) {
+                Some(inverse) => inverse,
+                None => panic!("{} has no inverse modulo {}", a, self.modulo),
+            }
         } else {
-            self.mul(self.mul(half, half), a)
+            self.normalize(a)
         };
+        let mut exp = b.unsigned_abs();
+        let mut ans = self.normalize(1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ans = self.mul(ans, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        return ans;
     }
     pub fn fact(&self, n: i64) -> i64 {
-        let mut ans = 1;
+        let mut ans = self.normalize(1);
         for i in 1..=n {
             ans = self.mul(ans, i);
         }
@@ -47,6 +78,7 @@ impl ModuloArithmetic {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::number_theory::mod_int::ModInt;
 
     const MODULO: i64 = 1_000_000_007;
 
@@ -66,7 +98,7 @@ mod tests {
         for _ in 0..1_000_000_000 {
             let a = rand::random::<i64>() % MODULO;
             let b = rand::random::<i64>() % MODULO;
-            let expected = (a + b) % MODULO;
+            let expected = (a + b).rem_euclid(MODULO);
             let actual = modulo_arithmetic.add(a, b);
             assert_eq!(actual, expected);
         }
@@ -78,7 +110,7 @@ mod tests {
         for _ in 0..1_000_000_000 {
             let a = rand::random::<i64>() % MODULO;
             let b = rand::random::<i64>() % MODULO;
-            let expected = (a - b + MODULO) % MODULO;
+            let expected = (a - b).rem_euclid(MODULO);
             let actual = modulo_arithmetic.sub(a, b);
             assert_eq!(actual, expected);
         }
@@ -90,7 +122,7 @@ mod tests {
         for _ in 0..1_000_000_000 {
             let a = rand::random::<i64>() % MODULO;
             let b = rand::random::<i64>() % MODULO;
-            let expected = (a * b) % MODULO;
+            let expected = (a * b).rem_euclid(MODULO);
             let actual = modulo_arithmetic.mul(a, b);
             assert_eq!(actual, expected);
         }
@@ -102,7 +134,7 @@ mod tests {
         for _ in 0..1_000_000_000 {
             let a = rand::random::<i64>() % MODULO;
             let b = rand::random::<u32>();
-            let expected = (a.pow(b)) % MODULO;
+            let expected = ModInt::<{ MODULO as u64 }>::from(a).pow(b as u64).value() as i64;
             let actual = modulo_arithmetic.bin_pow(a, b as i64);
             assert_eq!(actual, expected);
         }
@@ -114,9 +146,11 @@ mod tests {
         for _ in 0..1_000_000_000 {
             let a = rand::random::<i64>() % MODULO;
             let b = rand::random::<i64>() % MODULO;
-            let expected = (a / b) % MODULO;
+            if b == 0 {
+                continue;
+            }
             let actual = modulo_arithmetic.div(a, b);
-            assert_eq!(actual, expected);
+            assert_eq!(modulo_arithmetic.mul(actual, b), a.rem_euclid(MODULO));
         }
     }
 
@@ -129,4 +163,38 @@ mod tests {
         let mut actual = modulo_arithmetic.div(a - (a % b), b);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_negative_inputs() {
+        let modulo_arithmetic = ModuloArithmetic::new(MODULO);
+        assert_eq!(modulo_arithmetic.sub(-5, 3), MODULO - 8);
+        assert_eq!(modulo_arithmetic.add(-1, -1), MODULO - 2);
+        assert_eq!(modulo_arithmetic.mul(-2, 3), MODULO - 6);
+        assert_eq!(modulo_arithmetic.add(i64::MAX, i64::MIN), modulo_arithmetic.normalize(-1));
+        assert_eq!(modulo_arithmetic.bin_pow(2, -1), (MODULO + 1) / 2);
+        assert_eq!(modulo_arithmetic.bin_pow(-2, 3), MODULO - 8);
+        // 3^(i64::MIN) * 3^(i64::MAX) = 3^-1
+        let product = modulo_arithmetic.mul(modulo_arithmetic.bin_pow(3, i64::MIN), modulo_arithmetic.bin_pow(3, i64::MAX));
+        assert_eq!(Some(product), modulo_arithmetic.inv(3));
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        let modulo = (1 << 62) + 135;
+        let modulo_arithmetic = ModuloArithmetic::new(modulo);
+        assert_eq!(modulo_arithmetic.mul(-1, -1), 1);
+        assert_eq!(modulo_arithmetic.add(modulo - 1, modulo - 1), modulo - 2);
+        assert_eq!(modulo_arithmetic.sub(0, modulo - 1), 1);
+        assert_eq!(modulo_arithmetic.bin_pow(modulo - 1, 1_000_000_001), modulo - 1);
+    }
+
+    #[test]
+    fn test_composite_modulus() {
+        let modulo_arithmetic = ModuloArithmetic::new(12);
+        assert_eq!(modulo_arithmetic.inv(5), Some(5));
+        assert_eq!(modulo_arithmetic.inv(-5), Some(7));
+        assert_eq!(modulo_arithmetic.inv(4), None);
+        assert_eq!(modulo_arithmetic.div(7, 5), 11);
+        assert_eq!(modulo_arithmetic.fact(5), 0);
+    }
 }
\ No newline at end of file
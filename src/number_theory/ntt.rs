@@ -0,0 +1,302 @@
+/*
+    Number-Theoretic Transform
+    --------------------------------------------------------------
+    Polynomial multiplication (convolution) modulo a prime of the
+    form c * 2^k + 1, the FFT with the roots of unity taken in
+    Z/pZ so the result is exact.
+      * convolve: for NTT-friendly primes like 998244353, the result
+        length may not exceed the largest power of two dividing M - 1
+      * convolve_any_mod: any modulus below 2^31, the convolution is
+        computed under three NTT primes and recombined by CRT (Garner),
+        exact since a coefficient sums at most 2^23 products below 2^62
+        and 2^85 < M1 * M2 * M3
+    Inputs shorter than SCHOOLBOOK_THRESHOLD use the O(n * m) loop.
+    Time Complexity: O((n + m) log(n + m))
+    --------------------------------------------------------------
+ */
+use crate::number_theory::mod_int::ModInt;
+use crate::number_theory::pollard_rho::pow_mod;
+
+const SCHOOLBOOK_THRESHOLD: usize = 60;
+
+/// returns: the smallest primitive root of the prime `p`, `discrete_log::primitive_root` as a const fn that
+/// factorizes `p - 1` by trial division
+const fn __primitive_root_of_prime(p: u64) -> u64 {
+    if p == 2 {
+        return 1;
+    }
+    // a u64 has at most 15 distinct prime factors
+    let mut primes = [0u64; 15];
+    let mut count = 0;
+    let mut rest = p - 1;
+    let mut d = 2;
+    while d <= rest / d {
+        if rest % d == 0 {
+            primes[count] = d;
+            count += 1;
+            while rest % d == 0 {
+                rest /= d;
+            }
+        }
+        d += 1;
+    }
+    if rest > 1 {
+        primes[count] = rest;
+        count += 1;
+    }
+    let mut g = 2;
+    while g < p {
+        let mut i = 0;
+        while i < count && pow_mod(g, (p - 1) / primes[i], p) != 1 {
+            i += 1;
+        }
+        if i == count {
+            return g;
+        }
+        g += 1;
+    }
+    panic!("the NTT modulus has to be a prime");
+}
+
+/// The primitive root of `M`, evaluated at compile time once per modulus
+struct NttRoot<const M: u64>;
+
+impl<const M: u64> NttRoot<M> {
+    const ROOT: u64 = __primitive_root_of_prime(M);
+}
+
+fn __convolve_schoolbook<const M: u64>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    let mut c = vec![ModInt::default(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            c[i + j] += x * y;
+        }
+    }
+    c
+}
+
+/// In-place transform of `a`, whose length is a power of two, `invert` computes the inverse transform
+fn __ntt<const M: u64>(a: &mut [ModInt<M>], invert: bool, root: ModInt<M>) {
+    let n = a.len();
+    // bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        // principal len-th root of unity
+        let w = if invert { root.pow((M - 1) / len as u64).inv() } else { root.pow((M - 1) / len as u64) };
+        let half = len / 2;
+        let mut powers = Vec::with_capacity(half);
+        powers.push(ModInt::new(1));
+        for k in 1..half {
+            powers.push(powers[k - 1] * w);
+        }
+        for chunk in a.chunks_mut(len) {
+            for k in 0..half {
+                let u = chunk[k];
+                let v = chunk[k + half] * powers[k];
+                chunk[k] = u + v;
+                chunk[k + half] = u - v;
+            }
+        }
+        len <<= 1;
+    }
+    if invert {
+        let n_inv = ModInt::from(n).inv();
+        for x in a.iter_mut() {
+            *x *= n_inv;
+        }
+    }
+}
+
+/// # Arguments
+///
+/// * `a`, `b`: Coefficients of the two polynomials, lowest degree first
+///
+/// returns: the coefficients of `a * b`, `c[k] = sum of a[i] * b[k - i]`, empty if either is empty
+///
+/// # Examples
+///
+/// ```
+/// let a: Vec<ModInt998244353> = vec![1, 2, 3].into_iter().map(ModInt::from).collect();
+/// let b: Vec<ModInt998244353> = vec![4, 5].into_iter().map(ModInt::from).collect();
+/// let c = convolve(&a, &b); // [4, 13, 22, 15]
+/// ```
+#[allow(dead_code)]
+pub fn convolve<const M: u64>(a: &[ModInt<M>], b: &[ModInt<M>]) -> Vec<ModInt<M>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) <= SCHOOLBOOK_THRESHOLD {
+        return __convolve_schoolbook(a, b);
+    }
+    let result_len = a.len() + b.len() - 1;
+    let n = result_len.next_power_of_two();
+    assert!((M - 1) % n as u64 == 0, "{} has no {}-th roots of unity", M, n);
+    let root = ModInt::new(NttRoot::<M>::ROOT);
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+    fa.resize(n, ModInt::default());
+    fb.resize(n, ModInt::default());
+    __ntt(&mut fa, false, root);
+    __ntt(&mut fb, false, root);
+    for (x, &y) in fa.iter_mut().zip(fb.iter()) {
+        *x *= y;
+    }
+    __ntt(&mut fa, true, root);
+    fa.truncate(result_len);
+    fa
+}
+
+// 2^25 * 5 + 1, 2^26 * 7 + 1 and 2^24 * 45 + 1, together they cover lengths up to 2^24
+const M1: u64 = 167_772_161;
+const M2: u64 = 469_762_049;
+const M3: u64 = 754_974_721;
+
+fn __convolve_under<const M: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let a: Vec<ModInt<M>> = a.iter().map(|&x| ModInt::from(x)).collect();
+    let b: Vec<ModInt<M>> = b.iter().map(|&x| ModInt::from(x)).collect();
+    convolve(&a, &b).iter().map(|x| x.value()).collect()
+}
+
+/// # Arguments
+///
+/// * `a`, `b`: Coefficients of the two polynomials, lowest degree first
+/// * `m`: Any modulus in [1, 2^31)
+///
+/// returns: the coefficients of `a * b` modulo `m`, empty if either is empty
+///
+/// # Examples
+///
+/// ```
+/// let c = convolve_any_mod(&[1_000_000_006, 2], &[1_000_000_006, 3], 1_000_000_007); // [1, 1_000_000_002, 6], (-1 + 2x)(-1 + 3x) = 1 - 5x + 6x^2
+/// ```
+#[allow(dead_code)]
+pub fn convolve_any_mod(a: &[u64], b: &[u64], m: u64) -> Vec<u64> {
+    debug_assert!((1..1 << 31).contains(&m), "convolve_any_mod supports moduli in [1, 2^31), got {}", m);
+    let a: Vec<u64> = a.iter().map(|&x| x % m).collect();
+    let b: Vec<u64> = b.iter().map(|&x| x % m).collect();
+    let c1 = __convolve_under::<M1>(&a, &b);
+    let c2 = __convolve_under::<M2>(&a, &b);
+    let c3 = __convolve_under::<M3>(&a, &b);
+    // Garner: x = x1 + M1 * (k2 + M2 * k3) with every k_i reduced by its own prime
+    let m1_inv_m2 = ModInt::<M2>::new(M1).inv();
+    let m12_inv_m3 = (ModInt::<M3>::new(M1) * ModInt::new(M2)).inv();
+    let m12_mod_m = (M1 as u128 * M2 as u128 % m as u128) as u64;
+    (0..c1.len())
+        .map(|i| {
+            let (x1, x2, x3) = (c1[i], c2[i], c3[i]);
+            let k2 = ((ModInt::<M2>::new(x2) - ModInt::new(x1)) * m1_inv_m2).value();
+            // x1 + M1 * k2 < M1 * M2, the exact value modulo M1 * M2
+            let x12 = x1 as u128 + M1 as u128 * k2 as u128;
+            let k3 = ((ModInt::<M3>::from(x3) - ModInt::from(x12)) * m12_inv_m3).value();
+            ((x12 % m as u128) as u64 + k3 % m * m12_mod_m % m) % m
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::discrete_log::primitive_root;
+    use crate::number_theory::mod_int::ModInt998244353;
+
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+
+    type Mint = ModInt998244353;
+
+    fn mints(a: &[u64]) -> Vec<Mint> {
+        a.iter().map(|&x| Mint::from(x)).collect()
+    }
+
+    #[test]
+    fn test_convolve() {
+        assert_eq!(convolve(&mints(&[1, 2, 3]), &mints(&[4, 5])), mints(&[4, 13, 22, 15]));
+        assert_eq!(convolve(&mints(&[]), &mints(&[4, 5])), vec![]);
+        // long enough to go through the transform: (1 + x)^100 * (1 + x)^100 = (1 + x)^200
+        let binomial = |n: usize| {
+            let mut c = vec![Mint::new(1)];
+            for _ in 0..n {
+                c = __convolve_schoolbook(&c, &mints(&[1, 1]));
+            }
+            c
+        };
+        assert_eq!(convolve(&binomial(100), &binomial(100)), binomial(200));
+    }
+
+    #[test]
+    fn test_primitive_root_of_prime() {
+        assert_eq!(NttRoot::<998_244_353>::ROOT, 3);
+        for p in [2, 3, 7, M1, M2, M3, 1_000_000_007] {
+            assert_eq!(Some(__primitive_root_of_prime(p)), primitive_root(p), "{}", p);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "1000000007 has no 128-th roots of unity")]
+    fn test_convolve_without_roots_panics() {
+        // 10^9 + 6 = 2 * 500000003, only square roots of unity exist
+        let a = vec![ModInt::<1_000_000_007>::new(1); SCHOOLBOOK_THRESHOLD + 1];
+        convolve(&a, &a);
+    }
+
+    #[test]
+    fn test_convolve_any_mod() {
+        let m = 1_000_000_007;
+        assert_eq!(convolve_any_mod(&[m - 1, 2], &[m - 1, 3], m), vec![1, m - 5, 6]);
+        assert_eq!(convolve_any_mod(&[5], &[7], 1), vec![0]);
+        // worst case coefficients: n * (m - 1)^2 with the largest supported modulus
+        let m = (1 << 31) - 1;
+        let n = 1000;
+        let c = convolve_any_mod(&vec![m - 1; n], &vec![m - 1; n], m);
+        assert_eq!(c[n - 1], (n as u128 * (m - 1) as u128 * (m - 1) as u128 % m as u128) as u64);
+    }
+
+    fn convolve_prop(a: Vec<u64>, b: Vec<u64>, m: u64) {
+        let expected = if a.is_empty() || b.is_empty() {
+            vec![]
+        } else {
+            let mut c = vec![0u128; a.len() + b.len() - 1];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    c[i + j] += (x % m) as u128 * (y % m) as u128;
+                }
+            }
+            c.iter().map(|&x| (x % m as u128) as u64).collect::<Vec<u64>>()
+        };
+        assert_eq!(convolve_any_mod(&a, &b, m), expected);
+        let expected_mint: Vec<u64> = if a.is_empty() || b.is_empty() {
+            vec![]
+        } else {
+            __convolve_schoolbook(&mints(&a), &mints(&b)).iter().map(|x| x.value()).collect()
+        };
+        assert_eq!(convolve(&mints(&a), &mints(&b)).iter().map(|x| x.value()).collect::<Vec<u64>>(), expected_mint);
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn convolve_random(
+            a in vec(any::<u64>(), 0..=200).boxed(),
+            b in vec(any::<u64>(), 0..=200).boxed(),
+            m in prop_oneof![1u64..10, 1u64..1 << 31],
+        ) {
+            convolve_prop(a, b, m);
+        }
+    }
+}
\ No newline at end of file
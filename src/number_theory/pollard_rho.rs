@@ -0,0 +1,271 @@
+/*
+    Miller-Rabin and Pollard's Rho
+    --------------------------------------------------------------
+    Primality test and factorization for a single u64, no sieve.
+      * is_prime: Miller-Rabin with the 7 bases of Jim Sinclair,
+        deterministic for every n < 2^64
+        Time: O(7 * log n) modular multiplications
+      * factorize: trial division by the small primes, then
+        Pollard's rho with Brent's cycle detection, batching 128
+        differences per gcd
+        Time: O(n^(1/4)) expected multiplications per prime factor
+      * divisors: every divisor from the factorization
+    All products go through u128 so nothing overflows.
+    --------------------------------------------------------------
+ */
+use crate::number_theory::gcd::gcd_u64;
+
+const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+const MILLER_RABIN_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+/// returns: `a * b mod m` without overflow
+#[allow(dead_code)]
+pub const fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// returns: `a^exp mod m`
+#[allow(dead_code)]
+pub const fn pow_mod(a: u64, exp: u64, m: u64) -> u64 {
+    let (mut base, mut exp) = (a % m, exp);
+    let mut ans = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            ans = mul_mod(ans, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    ans
+}
+
+/// # Arguments
+///
+/// * `n`: Any u64
+///
+/// returns: true if `n` is prime, exact for the whole u64 range
+///
+/// # Examples
+///
+/// ```
+/// assert!(is_prime(1_000_000_007));
+/// assert!(!is_prime(1_000_000_016_000_000_063)); // (10^9 + 7) * (10^9 + 9)
+/// ```
+#[allow(dead_code)]
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    // n - 1 = d * 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'bases: for a in MILLER_RABIN_BASES {
+        let a = a % n;
+        if a == 0 {
+            continue;
+        }
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// returns: a non-trivial factor of the odd composite `n`
+fn __pollard_brent(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    let f = |x: u64, c: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+    // a failed run (the whole cycle collapsed into g = n) is retried with the next constant
+    for c in 1.. {
+        let (mut x, mut y, mut ys) = (0, 2, 2);
+        let (mut g, mut q, mut r) = (1, 1, 1);
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y, c);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y, c);
+                    q = mul_mod(q, x.abs_diff(y), n);
+                }
+                g = gcd_u64(q, n);
+                k += BATCH;
+            }
+            r *= 2;
+        }
+        if g == n {
+            // the batch overshot, redo it one step at a time from the last checkpoint
+            g = 1;
+            while g == 1 {
+                ys = f(ys, c);
+                g = gcd_u64(x.abs_diff(ys), n);
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+fn __factorize(n: u64, primes: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        primes.push(n);
+        return;
+    }
+    let d = __pollard_brent(n);
+    __factorize(d, primes);
+    __factorize(n / d, primes);
+}
+
+/// # Arguments
+///
+/// * `n`: Number to factorize, at least 1
+///
+/// returns: the `(prime, exponent)` pairs of `n` sorted by prime, empty for 1
+///
+/// # Examples
+///
+/// ```
+/// let factors = factorize(1_000_000_016_000_000_063); // [(1000000007, 1), (1000000009, 1)]
+/// let all = divisors(&factors); // [1, 1000000007, 1000000009, 1000000016000000063]
+/// ```
+#[allow(dead_code)]
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    assert!(n >= 1, "0 has no factorization");
+    let mut n = n;
+    let mut primes = Vec::new();
+    for p in SMALL_PRIMES {
+        while n % p == 0 {
+            primes.push(p);
+            n /= p;
+        }
+    }
+    __factorize(n, &mut primes);
+    primes.sort();
+
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, e)) if *last == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+/// returns: every divisor of the number whose factorization is `factors`, in increasing order
+#[allow(dead_code)]
+pub fn divisors(factors: &[(u64, u32)]) -> Vec<u64> {
+    let mut divisors = vec![1];
+    for &(p, e) in factors {
+        let current = divisors.len();
+        let mut power = 1;
+        for _ in 0..e {
+            power *= p;
+            for i in 0..current {
+                divisors.push(divisors[i] * power);
+            }
+        }
+    }
+    divisors.sort();
+    divisors
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::number_theory::sieve_of_eratosthenes::sieve_of_eratosthenes;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_is_prime() {
+        let primes = sieve_of_eratosthenes(100_000);
+        let mut expected = vec![false; 100_001];
+        for &p in &primes {
+            expected[p] = true;
+        }
+        for n in 0..=100_000 {
+            assert_eq!(is_prime(n as u64), expected[n], "{}", n);
+        }
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest u64 prime
+        assert!(!is_prime(u64::MAX));
+        // strong pseudoprimes to several small bases
+        assert!(!is_prime(3_215_031_751));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(1_000_000_016_000_000_063), vec![(1_000_000_007, 1), (1_000_000_009, 1)]);
+        assert_eq!(factorize(1 << 63), vec![(2, 63)]);
+        assert_eq!(factorize(u64::MAX), vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]);
+        assert_eq!(factorize(18_446_744_073_709_551_557), vec![(18_446_744_073_709_551_557, 1)]);
+        // square of a large prime
+        assert_eq!(factorize(4_294_967_291 * 4_294_967_291), vec![(4_294_967_291, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "0 has no factorization")]
+    fn test_factorize_zero_panics() {
+        factorize(0);
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(&factorize(1)), vec![1]);
+        assert_eq!(divisors(&factorize(12)), vec![1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(&factorize(1_000_000_016_000_000_063)), vec![1, 1_000_000_007, 1_000_000_009, 1_000_000_016_000_000_063]);
+        assert_eq!(divisors(&factorize(735_134_400)).len(), 1344);
+    }
+
+    fn factorize_prop(n: u64) {
+        let factors = factorize(n);
+        assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(factors.iter().all(|&(p, _)| is_prime(p)));
+        assert_eq!(factors.iter().map(|&(p, e)| p.pow(e)).product::<u64>(), n);
+        if n <= 10_000 {
+            let expected: Vec<u64> = (1..=n).filter(|d| n % d == 0).collect();
+            assert_eq!(divisors(&factors), expected);
+        }
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn factorize_random(n in prop_oneof![1u64..=10_000, 1u64..=u64::MAX]) {
+            factorize_prop(n);
+        }
+
+        #[test]
+        fn factorize_semiprime(a in 2u64..4_000_000_000, b in 2u64..4_000_000_000) {
+            factorize_prop(a * b);
+        }
+    }
+}
\ No newline at end of file
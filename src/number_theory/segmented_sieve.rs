@@ -0,0 +1,259 @@
+/*
+    Segmented Sieve
+    --------------------------------------------------------------
+    Sieve variants for when sieve_of_eratosthenes' one bool per
+    number is too much memory.
+      * segmented_sieve: primes in [low, high] crossing out with the
+        base primes up to sqrt(high) only, for high up to ~10^12 and
+        high - low up to ~10^7
+        Time: O((high - low) log log high + sqrt(high)), Space: O(high - low + sqrt(high))
+      * OddSieve: one bit per odd number, n / 16 bytes
+        Time: O(n log log n), Space: O(n / 64) words
+      * Primes: endless iterator over the primes, sieving one block
+        of 2^16 numbers at a time
+    --------------------------------------------------------------
+ */
+use crate::number_theory::sieve_of_eratosthenes::sieve_of_eratosthenes;
+
+/// returns: floor(sqrt(x)), the squares go through u128 so x may be anything up to u64::MAX
+fn isqrt(x: u64) -> u64 {
+    let mut r = (x as f64).sqrt() as u64;
+    while r as u128 * r as u128 > x as u128 {
+        r -= 1;
+    }
+    while (r as u128 + 1) * (r as u128 + 1) <= x as u128 {
+        r += 1;
+    }
+    r
+}
+
+/// returns: the primes in [low, high], `base_primes` has to contain every prime up to sqrt(high)
+fn __sieve_segment(low: u64, high: u64, base_primes: &[usize]) -> Vec<u64> {
+    let low = low.max(2);
+    if low > high {
+        return Vec::new();
+    }
+    let mut is_prime = vec![true; (high - low + 1) as usize];
+    for &p in base_primes {
+        let p = p as u64;
+        if p * p > high {
+            break;
+        }
+        // multiples below p * p have a smaller prime factor, near u64::MAX the next multiple may not exist
+        let mut multiple = low.div_ceil(p).checked_mul(p).map(|first| (p * p).max(first));
+        while let Some(m) = multiple.filter(|&m| m <= high) {
+            is_prime[(m - low) as usize] = false;
+            multiple = m.checked_add(p);
+        }
+    }
+    (low..=high).filter(|&x| is_prime[(x - low) as usize]).collect()
+}
+
+/// # Arguments
+///
+/// * `low`, `high`: Inclusive range to find the primes in
+///
+/// returns: the primes in [low, high] in increasing order
+///
+/// # Examples
+///
+/// ```
+/// let primes = segmented_sieve(1_000_000_000_000, 1_000_000_000_100); // [1000000000039, 1000000000061, 1000000000063, 1000000000091]
+/// ```
+#[allow(dead_code)]
+pub fn segmented_sieve(low: u64, high: u64) -> Vec<u64> {
+    let base_primes = sieve_of_eratosthenes(isqrt(high).max(1) as usize);
+    __sieve_segment(low, high, &base_primes)
+}
+
+/// Bit-packed sieve over the odd numbers only, bit `i` is set when `2i + 1` is composite
+pub struct OddSieve {
+    upper_limit: usize,
+    composite: Vec<u64>,
+}
+
+#[allow(dead_code)]
+impl OddSieve {
+    pub fn new(upper_limit: usize) -> Self {
+        let odd_count = upper_limit / 2 + 1;
+        let mut composite = vec![0u64; odd_count.div_ceil(64)];
+        // 1 is not a prime
+        composite[0] |= 1;
+        let mut p = 3;
+        while p * p <= upper_limit {
+            if composite[p / 2 / 64] >> (p / 2 % 64) & 1 == 0 {
+                // odd multiples of p are p apart in the index space
+                let mut i = p * p / 2;
+                while i < odd_count {
+                    composite[i / 64] |= 1 << (i % 64);
+                    i += p;
+                }
+            }
+            p += 2;
+        }
+        OddSieve { upper_limit, composite }
+    }
+
+    pub fn is_prime(&self, x: usize) -> bool {
+        debug_assert!(x <= self.upper_limit, "{} is above the sieved limit {}", x, self.upper_limit);
+        if x & 1 == 0 {
+            return x == 2;
+        }
+        self.composite[x / 2 / 64] >> (x / 2 % 64) & 1 == 0
+    }
+
+    /// returns: the primes up to `upper_limit` in increasing order
+    pub fn primes(&self) -> Vec<usize> {
+        let mut primes = Vec::new();
+        if self.upper_limit >= 2 {
+            primes.push(2);
+        }
+        for (w, &word) in self.composite.iter().enumerate() {
+            let mut primes_in_word = !word;
+            while primes_in_word != 0 {
+                let p = 2 * (w * 64 + primes_in_word.trailing_zeros() as usize) + 1;
+                if p > self.upper_limit {
+                    break;
+                }
+                primes.push(p);
+                primes_in_word &= primes_in_word - 1;
+            }
+        }
+        primes
+    }
+
+    /// returns: the number of primes up to `upper_limit`
+    pub fn count(&self) -> usize {
+        self.primes().len()
+    }
+}
+
+const PRIMES_BLOCK: u64 = 1 << 16;
+
+/// Endless iterator over the primes in increasing order
+///
+/// # Examples
+///
+/// ```
+/// let first_ten: Vec<u64> = Primes::new().take(10).collect();
+/// let prime_10001 = Primes::new().nth(10_000);
+/// ```
+pub struct Primes {
+    // primes of the current block that are not returned yet, in decreasing order
+    block: Vec<u64>,
+    // the next block starts here
+    next_low: u64,
+    // every prime up to `base_limit`, regrown by doubling
+    base_primes: Vec<usize>,
+    base_limit: u64,
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Primes {
+    pub fn new() -> Self {
+        Primes { block: Vec::new(), next_low: 0, base_primes: Vec::new(), base_limit: 0 }
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.block.is_empty() {
+            let low = self.next_low;
+            let high = low + PRIMES_BLOCK - 1;
+            if isqrt(high) > self.base_limit {
+                self.base_limit = (2 * isqrt(high)).max(PRIMES_BLOCK);
+                self.base_primes = sieve_of_eratosthenes(self.base_limit as usize);
+            }
+            self.block = __sieve_segment(low, high, &self.base_primes);
+            self.block.reverse();
+            self.next_low = high + 1;
+        }
+        self.block.pop()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn is_prime_naive(x: u64) -> bool {
+        x >= 2 && (2..).take_while(|d| d * d <= x).all(|d| x % d > 0)
+    }
+
+    #[test]
+    fn test_segmented_sieve() {
+        assert_eq!(segmented_sieve(0, 30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(segmented_sieve(0, 0), vec![]);
+        assert_eq!(segmented_sieve(24, 28), vec![]);
+        assert_eq!(segmented_sieve(1_000_000_000_000, 1_000_000_000_100), vec![1_000_000_000_039, 1_000_000_000_061, 1_000_000_000_063, 1_000_000_000_091]);
+        // pi(10^12 + 10^6) - pi(10^12) = 36249
+        assert_eq!(segmented_sieve(1_000_000_000_000, 1_000_000_000_000 + 1_000_000).len(), 36249);
+    }
+
+    #[test]
+    fn test_near_u64_max() {
+        assert_eq!(isqrt(u64::MAX), (1 << 32) - 1);
+        assert_eq!(isqrt((1 << 62) - 1), (1 << 31) - 1);
+        // only the primes up to 1000 cross out, the survivors are the numbers without a factor below 1000
+        let base_primes = sieve_of_eratosthenes(1000);
+        let low = u64::MAX - 1000;
+        let expected: Vec<u64> = (low..=u64::MAX).filter(|&x| base_primes.iter().all(|&p| x % p as u64 != 0)).collect();
+        assert_eq!(__sieve_segment(low, u64::MAX, &base_primes), expected);
+        assert!(expected.contains(&18_446_744_073_709_551_557));
+    }
+
+    #[test]
+    fn test_odd_sieve() {
+        let sieve = OddSieve::new(100);
+        assert_eq!(sieve.primes(), sieve_of_eratosthenes(100));
+        assert!(sieve.is_prime(97) && sieve.is_prime(2) && !sieve.is_prime(91) && !sieve.is_prime(1) && !sieve.is_prime(0));
+        assert!(OddSieve::new(1).primes().is_empty());
+        assert_eq!(OddSieve::new(2).primes(), vec![2]);
+        assert_eq!(OddSieve::new(10usize.pow(6)).count(), 78498);
+    }
+
+    #[test]
+    fn test_primes_iterator() {
+        let first: Vec<u64> = Primes::new().take(10).collect();
+        assert_eq!(first, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(Primes::new().nth(10_000), Some(104_743));
+        // crosses several blocks
+        let below: Vec<u64> = Primes::new().take_while(|&p| p <= 1_000_000).collect();
+        assert_eq!(below, sieve_of_eratosthenes(1_000_000).iter().map(|&p| p as u64).collect::<Vec<u64>>());
+    }
+
+    fn sieves_agree_prop(low: u64, len: u64, upper_limit: usize) {
+        let expected: Vec<u64> = (low..=low + len).filter(|&x| is_prime_naive(x)).collect();
+        assert_eq!(segmented_sieve(low, low + len), expected);
+        let sieve = OddSieve::new(upper_limit);
+        for x in 0..=upper_limit {
+            assert_eq!(sieve.is_prime(x), is_prime_naive(x as u64));
+        }
+        assert_eq!(sieve.primes(), sieve_of_eratosthenes(upper_limit.max(1)));
+    }
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            fork: true,
+            // timeout: 1000,
+            .. ProptestConfig::default()
+        })]
+        #[test]
+        fn sieves_agree(
+            low in prop_oneof![0u64..1000, 0u64..1_000_000_000],
+            len in 0u64..300,
+            upper_limit in 0usize..2000,
+        ) {
+            sieves_agree_prop(low, len, upper_limit);
+        }
+    }
+}
\ No newline at end of file